@@ -1,40 +1,155 @@
 #![allow(unstable_name_collisions)]
 
 use std::cell::Cell;
+use std::collections::TryReserveError;
 use std::ops::{Deref, DerefMut};
 
+pub mod rank;
 pub mod slice;
 
+/// The storage for the bits, bits are stored from the least significant bit
+/// to the most significant bit of each block
+pub type Block = usize;
+
+/// The number of bits in a `Block`
+pub const BITS: usize = Block::BITS as usize;
+
 fn index_to_slot(index: usize) -> (usize, u8) {
-    let slot = index >> 3;
-    let offset = (index & 0b0111) as u8;
+    let slot = index / BITS;
+    let offset = (index % BITS) as u8;
 
     (slot, offset)
 }
 
-fn set_bit(slot: &mut u8, offset: u8, value: bool) {
-    *slot = (*slot & !(1 << offset)) | ((value as u8) << offset);
+/// The number of blocks needed to hold `len` bits
+fn block_count(len: usize) -> usize {
+    len.div_ceil(BITS)
 }
 
-fn get_bit(slot: u8, offset: u8) -> bool {
+fn set_bit(slot: &mut Block, offset: u8, value: bool) {
+    *slot = (*slot & !(1 << offset)) | ((value as Block) << offset);
+}
+
+fn get_bit(slot: Block, offset: u8) -> bool {
     (slot & (1 << offset)) != 0
 }
 
-#[derive(Default, Clone)]
-pub struct BitVec {
-    data: Vec<u8>,
+/// A block with the lowest `n` bits set
+fn mask(n: u8) -> Block {
+    if n as usize >= BITS {
+        !0
+    } else {
+        (1 << n) - 1
+    }
+}
+
+/// Reads `n` bits starting at bit `index` of `ptr`
+///
+/// # Safety
+///
+/// `n <= BITS` and all of the blocks that contain the bits must be valid for reads
+unsafe fn load_bits(ptr: *const Block, index: usize, n: u8) -> Block {
+    let (slot, offset) = index_to_slot(index);
+
+    let mut value = *ptr.add(slot) >> offset;
+
+    if offset as usize + n as usize > BITS {
+        value |= *ptr.add(slot + 1) << (BITS - offset as usize);
+    }
+
+    value & mask(n)
+}
+
+/// Writes the lowest `n` bits of `value` starting at bit `index` of `ptr`
+///
+/// # Safety
+///
+/// `n <= BITS` and all of the blocks that contain the bits must be valid for writes,
+/// if `n == 0` nothing is read or written
+unsafe fn store_bits(ptr: *mut Block, index: usize, n: u8, value: Block) {
+    if n == 0 {
+        return;
+    }
+
+    let (slot, offset) = index_to_slot(index);
+
+    let mask = mask(n);
+    let value = value & mask;
+
+    let block = &mut *ptr.add(slot);
+    *block = (*block & !(mask << offset)) | (value << offset);
+
+    if offset as usize + n as usize > BITS {
+        let shift = BITS - offset as usize;
+        let block = &mut *ptr.add(slot + 1);
+        *block = (*block & !(mask >> shift)) | (value >> shift);
+    }
+}
+
+/// Copies `len` bits from bit `src` of `src_ptr` to bit `dest` of `dest_ptr` one block at a time
+///
+/// # Safety
+///
+/// All of the blocks that contain the bits must be valid for reads/writes,
+/// and if the bits overlap then the destination must be before the source
+unsafe fn copy_bits(
+    src_ptr: *const Block,
+    src: usize,
+    dest_ptr: *mut Block,
+    dest: usize,
     len: usize,
+) {
+    let mut copied = 0;
+
+    while copied < len {
+        let n = (len - copied).min(BITS) as u8;
+        let value = load_bits(src_ptr, src + copied, n);
+        store_bits(dest_ptr, dest + copied, n, value);
+        copied += n as usize;
+    }
+}
+
+/// Sets `len` bits starting at bit `index` of `ptr` to `value`, all whole blocks
+/// are filled with a single `memset`
+///
+/// # Safety
+///
+/// All of the blocks that contain the bits must be valid for writes
+unsafe fn fill_bits(ptr: *mut Block, index: usize, len: usize, value: bool) {
+    let block = if value { !0 } else { 0 };
+    let end = index + len;
+    let mut index = index;
+
+    // first partial block
+    let offset = index % BITS;
+    if offset != 0 {
+        let n = (BITS - offset).min(len);
+        store_bits(ptr, index, n as u8, block);
+        index += n;
+    }
+
+    // whole blocks
+    let blocks = (end - index) / BITS;
+    std::ptr::write_bytes(ptr.add(index / BITS), block as u8, blocks);
+    index += blocks * BITS;
+
+    // last partial block
+    if index < end {
+        store_bits(ptr, index, (end - index) as u8, block);
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct AllocInfo {
-    pub len: usize,
-    pub cap: usize,
-    _priv: (),
+/// A growable array of bits, stored in `Block`s
+///
+/// All bits past `len` in the allocated blocks are kept zeroed by the safe api
+#[derive(Default)]
+pub struct BitVec {
+    data: Vec<Block>,
+    len: usize,
 }
 
 pub struct BitProxy<'a> {
-    slot: &'a Cell<u8>,
+    slot: &'a Cell<Block>,
     offset: u8,
     value: bool,
 }
@@ -67,26 +182,49 @@ impl BitProxy<'_> {
     }
 }
 
+impl Clone for BitVec {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            len: self.len,
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.data.clone_from(&source.data);
+        self.len = source.len;
+    }
+}
+
 impl BitVec {
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Creates an empty `BitVec` with space for at least `cap` bits
     pub fn with_capacity(cap: usize) -> Self {
         Self {
-            data: Vec::with_capacity(cap),
+            data: Vec::with_capacity(block_count(cap)),
             len: 0,
         }
     }
 
-    pub fn alloc_info(&self) -> AllocInfo {
-        AllocInfo {
-            len: self.data.len(),
-            cap: self.data.capacity(),
-            _priv: (),
+    /// Creates a `BitVec` with `len` bits that are all set to false
+    ///
+    /// This uses a zeroed allocation, so the memory isn't touched until it is used
+    pub fn zeroed(len: usize) -> Self {
+        Self {
+            // `vec![0; n]` allocates with `alloc_zeroed`
+            data: vec![0; block_count(len)],
+            len,
         }
     }
 
+    /// The number of bits that can be held without reallocating
+    pub fn capacity(&self) -> usize {
+        self.data.capacity().saturating_mul(BITS)
+    }
+
     pub fn len(&self) -> usize {
         self.len
     }
@@ -95,12 +233,37 @@ impl BitVec {
         self.len == 0
     }
 
+    /// The number of blocks that need to be reserved to hold `additional` more bits
+    fn additional_blocks(&self, additional: usize) -> usize {
+        block_count(self.len.saturating_add(additional)).saturating_sub(self.data.len())
+    }
+
     pub fn reserve(&mut self, additional: usize) {
-        self.data.reserve((additional >> 3) + 1);
+        self.data.reserve(self.additional_blocks(additional));
     }
 
     pub fn reserve_exact(&mut self, additional: usize) {
-        self.data.reserve_exact((additional >> 3) + 1);
+        self.data.reserve_exact(self.additional_blocks(additional));
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.data.try_reserve(self.additional_blocks(additional))
+    }
+
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.data
+            .try_reserve_exact(self.additional_blocks(additional))
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.data.truncate(block_count(self.len));
+        self.data.shrink_to_fit();
+    }
+
+    /// Shrinks the capacity to hold at least `min_capacity` bits
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.data.truncate(block_count(self.len));
+        self.data.shrink_to(block_count(min_capacity));
     }
 
     pub fn push(&mut self, value: bool) -> BitProxy<'_> {
@@ -128,7 +291,91 @@ impl BitVec {
 
         let (slot, offset) = index_to_slot(self.len);
 
-        unsafe { Some(get_bit(*self.data.get_unchecked(slot), offset)) }
+        unsafe {
+            let slot = self.data.get_unchecked_mut(slot);
+            let value = get_bit(*slot, offset);
+
+            // keep the bits past the end zeroed
+            set_bit(slot, offset, false);
+
+            Some(value)
+        }
+    }
+
+    /// Inserts `value` at `index`, shifting all bits after it to the right
+    /// one block at a time
+    pub fn insert(&mut self, index: usize, value: bool) {
+        assert!(index <= self.len, "Index is out of bounds!");
+
+        // make space for the new bit
+        self.push(false);
+
+        let (slot, offset) = index_to_slot(index);
+        let (last, _) = index_to_slot(self.len - 1);
+
+        // all slots in `slot..=last` are in bounds, because `push` made sure
+        // that there is a slot for the last bit
+        unsafe {
+            let block = self.data.get_unchecked_mut(slot);
+            let low = mask(offset);
+            let mut carry = *block >> (BITS - 1);
+
+            *block = (*block & low) | ((*block & !low) << 1) | ((value as Block) << offset);
+
+            for block in self.data.get_unchecked_mut(slot + 1..=last) {
+                let next_carry = *block >> (BITS - 1);
+                *block = (*block << 1) | carry;
+                carry = next_carry;
+            }
+        }
+    }
+
+    /// Removes the bit at `index`, shifting all bits after it to the left
+    /// one block at a time
+    pub fn remove(&mut self, index: usize) -> bool {
+        assert!(index < self.len, "Index is out of bounds!");
+
+        let (slot, offset) = index_to_slot(index);
+        let (last, _) = index_to_slot(self.len - 1);
+
+        self.len -= 1;
+
+        // all slots in `slot..=last` are in bounds, because `last` corrosponds to
+        // the old last bit
+        unsafe {
+            let block = *self.data.get_unchecked(slot);
+            let low = mask(offset);
+            let value = get_bit(block, offset);
+
+            let mut next = (block & low) | ((block >> 1) & !low);
+
+            for i in slot..last {
+                let block = *self.data.get_unchecked(i + 1);
+
+                *self.data.get_unchecked_mut(i) = next | (block << (BITS - 1));
+                next = block >> 1;
+            }
+
+            *self.data.get_unchecked_mut(last) = next;
+
+            value
+        }
+    }
+
+    /// Removes the bit at `index`, and replaces it with the last bit
+    pub fn swap_remove(&mut self, index: usize) -> bool {
+        assert!(index < self.len, "Index is out of bounds!");
+
+        let value = unsafe { self.get_unchecked(index) };
+
+        // there is at least one element, checked above
+        let last = unsafe { self.pop().unwrap_unchecked() };
+
+        if index < self.len {
+            self.set(index, last);
+        }
+
+        value
     }
 
     pub fn get<'a, S: slice::SliceIndex<slice::BitSlice<'a>>>(
@@ -159,46 +406,112 @@ impl BitVec {
         self.as_mut_slice().into_get_unchecked_mut(index)
     }
 
+    /// Reads the bit at `index`, which may be past the end of the `BitVec`
+    ///
+    /// # Safety
+    ///
+    /// The bit must have been initialized before the length of the `BitVec` was decreased
+    pub unsafe fn read_unchecked(&self, index: usize) -> bool {
+        let (slot, offset) = index_to_slot(index);
+
+        get_bit(*self.data.get_unchecked(slot), offset)
+    }
+
+    /// Writes the bit at `index`, which may be past the end of the `BitVec`
+    ///
+    /// # Safety
+    ///
+    /// The bit must have been initialized before the length of the `BitVec` was decreased
+    pub unsafe fn write_unchecked(&mut self, index: usize, value: bool) {
+        let (slot, offset) = index_to_slot(index);
+
+        set_bit(self.data.get_unchecked_mut(slot), offset, value);
+    }
+
+    /// Copies `len` bits from `src` to `dest`, both of which may be past the end of the `BitVec`
+    ///
+    /// # Safety
+    ///
+    /// All of the bits in `src..src + len` must have been initialized before the length of
+    /// the `BitVec` was decreased, and `dest <= src`
+    pub unsafe fn copy_within_unchecked(&mut self, src: usize, dest: usize, len: usize) {
+        debug_assert!(dest <= src);
+        debug_assert!(src + len <= self.data.len() * BITS);
+
+        let ptr = self.data.as_mut_ptr();
+
+        copy_bits(ptr, src, ptr, dest, len);
+    }
+
+    /// # Safety
+    ///
+    /// If the length is increased, the new bits must have been initialized
+    /// before the length was decreased
+    ///
+    /// If the length is decreased, the bits past the new length are not cleared
     pub unsafe fn set_len(&mut self, len: usize) {
         self.len = len;
     }
 
+    /// Reduces the length to `len`, and clears all bits after it
+    ///
+    /// If `len` is greater than the length, nothing happens
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            // all bits in `len..self.len` are in bounds
+            unsafe {
+                fill_bits(self.data.as_mut_ptr(), len, self.len - len, false);
+            }
+
+            self.len = len;
+        }
+    }
+
     pub fn set(&mut self, index: usize, value: bool) {
         self.as_mut_slice().set(index, value);
     }
 
-    pub fn grow(&mut self, additional: usize, value: bool) {
+    /// Makes sure that there are enough blocks to hold `additional` more bits
+    fn grow_blocks(&mut self, additional: usize) {
         let new_len = self
             .len
             .checked_add(additional)
             .expect("Capacity overflow!");
 
-        self.data.resize(((self.len + additional) >> 3) + 1, 0);
+        let blocks = block_count(new_len);
 
-        let (slot, offset) = index_to_slot(self.len);
+        if blocks > self.data.len() {
+            self.data.resize(blocks, 0);
+        }
+    }
 
-        {
-            let slot = unsafe { self.data.get_unchecked_mut(slot) };
+    /// Appends the lowest `n` bits of `block`
+    pub fn push_block(&mut self, block: Block, n: u8) {
+        debug_assert!(n as usize <= BITS);
 
-            for offset in offset..8 {
-                set_bit(slot, offset, value);
-            }
+        if n == 0 {
+            return;
         }
 
-        let blocks = new_len.saturating_sub(self.len + (8u8 - offset) as usize);
-        self.len = new_len;
+        self.grow_blocks(n as usize);
 
-        let block_value: u8 = if value { !0 } else { 0 };
+        // `grow_blocks` made sure that all the blocks are in bounds
+        unsafe {
+            store_bits(self.data.as_mut_ptr(), self.len, n, block);
+        }
 
-        if blocks != 0 {
-            let blocks = (blocks >> 3) + 1;
-            let slot = slot + 1;
-            let iter = unsafe { self.data.get_unchecked_mut(slot..).iter_mut().take(blocks) };
+        self.len += n as usize;
+    }
 
-            for block in iter {
-                *block = block_value;
-            }
+    pub fn grow(&mut self, additional: usize, value: bool) {
+        self.grow_blocks(additional);
+
+        // `grow_blocks` made sure that all the blocks are in bounds
+        unsafe {
+            fill_bits(self.data.as_mut_ptr(), self.len, additional, value);
         }
+
+        self.len += additional;
     }
 
     pub fn clear(&mut self) {
@@ -206,12 +519,16 @@ impl BitVec {
         self.len = 0;
     }
 
-    pub fn set_all(&mut self, value: bool) {
-        let value = if value { !0 } else { 0 };
+    /// Converts into the blocks that hold the bits, the bits past `len` are zeroed
+    ///
+    /// Like `Vec::into_boxed_slice`, this reallocates if there is spare capacity
+    pub fn into_boxed_blocks(mut self) -> Box<[Block]> {
+        self.data.truncate(block_count(self.len));
+        self.data.into_boxed_slice()
+    }
 
-        for i in &mut self.data {
-            *i = value;
-        }
+    pub fn set_all(&mut self, value: bool) {
+        self.as_mut_slice().set_all(value);
     }
 
     pub fn iter(&self) -> slice::Iter<'_> {
@@ -223,24 +540,44 @@ impl BitVec {
     }
 }
 
+#[derive(Clone)]
 pub struct IntoIter {
     vec: BitVec,
     index: usize,
 }
 
+impl IntoIter {
+    pub fn as_slice(&self) -> slice::BitSlice<'_> {
+        // index is always less than or equal to `vec.len`
+        unsafe { self.vec.get_unchecked(self.index..) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> slice::BitSliceMut<'_> {
+        let index = self.index;
+
+        // index is always less than or equal to `vec.len`
+        unsafe { self.vec.get_unchecked_mut(index..) }
+    }
+}
+
 impl Iterator for IntoIter {
     type Item = bool;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let ret = self.vec.get(self.index)?;
+        if self.index < self.vec.len {
+            let (slot, offset) = index_to_slot(self.index);
 
-        self.index = self.index.saturating_add(1);
+            self.index += 1;
 
-        Some(ret)
+            // slot is in bounds because index is less than `vec.len`
+            unsafe { Some(get_bit(*self.vec.data.get_unchecked(slot), offset)) }
+        } else {
+            None
+        }
     }
 
     fn nth(&mut self, n: usize) -> Option<Self::Item> {
-        self.index = self.index.saturating_add(n);
+        self.index = self.index.saturating_add(n).min(self.vec.len);
 
         self.next()
     }
@@ -263,7 +600,7 @@ impl DoubleEndedIterator for IntoIter {
 
     #[cfg(feature = "nightly")]
     fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
-        self.vec.len = self.vec.len.saturating_sub(n);
+        self.vec.len = self.vec.len.saturating_sub(n).max(self.index);
 
         self.next_back()
     }
@@ -378,3 +715,99 @@ fn bit_vec() {
 
     assert!((0..100).map(|_| false).eq(vec.iter()));
 }
+
+#[test]
+fn insert_remove() {
+    let mut vec = BitVec::new();
+    let mut model = Vec::new();
+
+    for i in 0..50 {
+        let value = i % 3 == 0;
+        let index = (i * 7) % (model.len() + 1);
+
+        vec.insert(index, value);
+        model.insert(index, value);
+
+        assert!(vec.iter().eq(model.iter().copied()));
+    }
+
+    for i in 0..25 {
+        let index = (i * 5) % model.len();
+
+        assert_eq!(vec.remove(index), model.remove(index));
+        assert!(vec.iter().eq(model.iter().copied()));
+
+        let index = (i * 11) % model.len();
+
+        assert_eq!(vec.swap_remove(index), model.swap_remove(index));
+        assert!(vec.iter().eq(model.iter().copied()));
+    }
+
+    assert!(vec.is_empty());
+}
+
+#[test]
+fn copy_within() {
+    let mut vec = BitVec::new();
+    let model = (0..100)
+        .map(|i| i % 3 == 0 || i % 7 == 0)
+        .collect::<Vec<_>>();
+
+    for &bit in &model {
+        vec.push(bit);
+    }
+
+    for &(src, dest, len) in &[(10, 3, 40), (17, 17, 20), (64, 0, 36), (9, 8, 91)] {
+        let mut vec = vec.clone();
+        let mut model = model.clone();
+
+        unsafe {
+            vec.copy_within_unchecked(src, dest, len);
+        }
+
+        model.copy_within(src..src + len, dest);
+
+        assert!(vec.iter().eq(model.iter().copied()));
+    }
+}
+
+#[test]
+fn padding() {
+    fn assert_padding_zeroed(vec: &BitVec) {
+        let (slot, offset) = index_to_slot(vec.len);
+
+        if offset != 0 {
+            assert_eq!(vec.data[slot] & !mask(offset), 0);
+        }
+
+        assert!(vec.data[block_count(vec.len)..]
+            .iter()
+            .all(|&block| block == 0));
+    }
+
+    let mut vec = BitVec::new();
+
+    vec.grow(3, true);
+    vec.set_all(true);
+    assert_padding_zeroed(&vec);
+
+    vec.grow(BITS * 2 + 5, true);
+    vec.pop();
+    assert_padding_zeroed(&vec);
+
+    vec.remove(1);
+    vec.swap_remove(BITS);
+    assert_padding_zeroed(&vec);
+
+    vec.truncate(BITS - 1);
+    assert_padding_zeroed(&vec);
+    assert!(vec.iter().all(|bit| bit));
+
+    let tail = vec.split_off(5);
+    assert_padding_zeroed(&vec);
+    assert_padding_zeroed(&tail);
+
+    vec.grow(BITS, false);
+    assert!(vec.iter().take(5).all(|bit| bit));
+    assert!(vec.iter().skip(5).all(|bit| !bit));
+}
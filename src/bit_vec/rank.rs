@@ -0,0 +1,239 @@
+use super::slice::BitSlice;
+use super::{block_count, mask, Block, BITS};
+
+/// The number of blocks in each superblock of a `RankTable`
+const SUPERBLOCK: usize = 8;
+
+/// The number of set bits before each block of a bit slice
+///
+/// The counts are split into an absolute count for each superblock, and a count
+/// for each block that is relative to the start of its superblock
+#[derive(Clone)]
+struct RankTable {
+    /// the number of set bits before each superblock, followed by the total
+    supers: Box<[usize]>,
+    /// the number of set bits before each block, within its superblock
+    blocks: Box<[u16]>,
+}
+
+impl RankTable {
+    fn new(slice: BitSlice<'_>) -> Self {
+        let block_count = block_count(slice.len());
+        let mut supers = Vec::with_capacity(block_count.div_ceil(SUPERBLOCK) + 1);
+        let mut blocks = Vec::with_capacity(block_count);
+        let mut total = 0;
+        let mut relative = 0;
+
+        for i in 0..block_count {
+            if i % SUPERBLOCK == 0 {
+                supers.push(total);
+                relative = 0;
+            }
+
+            // a superblock has at most `SUPERBLOCK * BITS` bits, which fits in a `u16`
+            blocks.push(relative as u16);
+
+            let count = slice.load_block(i).count_ones() as usize;
+            relative += count;
+            total += count;
+        }
+
+        supers.push(total);
+
+        Self {
+            supers: supers.into_boxed_slice(),
+            blocks: blocks.into_boxed_slice(),
+        }
+    }
+}
+
+/// A rank/select index over a `BitSlice`
+#[derive(Clone)]
+pub struct RankIndex<'a> {
+    slice: BitSlice<'a>,
+    table: RankTable,
+}
+
+impl<'a> RankIndex<'a> {
+    pub fn new(slice: BitSlice<'a>) -> Self {
+        Self {
+            slice,
+            table: RankTable::new(slice),
+        }
+    }
+
+    /// Counts the number of bits that are set
+    pub fn count_ones(&self) -> usize {
+        self.table.supers[self.table.supers.len() - 1]
+    }
+
+    /// Counts the number of bits that are set before `index`
+    ///
+    /// Panics if `index > len`
+    pub fn rank(&self, index: usize) -> usize {
+        let len = self.slice.len();
+
+        assert!(
+            index <= len,
+            "rank index (is {}) should be <= len (is {})",
+            index,
+            len
+        );
+
+        let (i, n) = (index / BITS, index % BITS);
+
+        if i == self.table.blocks.len() {
+            return self.count_ones();
+        }
+
+        let rank = self.table.supers[i / SUPERBLOCK] + self.table.blocks[i] as usize;
+
+        if n == 0 {
+            rank
+        } else {
+            let block = self.slice.load_block(i) & mask(n as u8);
+
+            rank + block.count_ones() as usize
+        }
+    }
+
+    /// Finds the index of the `k`-th set bit (counting from zero)
+    pub fn select(&self, k: usize) -> Option<usize> {
+        if k >= self.count_ones() {
+            return None;
+        }
+
+        let RankTable { supers, blocks } = &self.table;
+
+        // the last superblock that starts with at most `k` set bits before it,
+        // this always exists because `supers[0] == 0`
+        let s = supers.partition_point(|&rank| rank <= k) - 1;
+        let k = k - supers[s];
+
+        let start = s * SUPERBLOCK;
+        let end = blocks.len().min(start + SUPERBLOCK);
+
+        // the same search within the superblock, `blocks[start] == 0`
+        let i = start + blocks[start..end].partition_point(|&rank| rank as usize <= k) - 1;
+        let block = self.slice.load_block(i);
+
+        Some(i * BITS + select_in_block(block, (k - blocks[i] as usize) as u32))
+    }
+}
+
+/// Finds the index of the `k`-th set bit in `block`, `k` must be less than `block.count_ones()`
+pub fn select_in_block(block: Block, k: u32) -> usize {
+    debug_assert!(k < block.count_ones());
+
+    select_in_word(block as u64, k) as usize
+}
+
+/// Deposits a single bit at the position of the `k`-th set bit of `word`
+#[cfg(all(target_arch = "x86_64", target_feature = "bmi2"))]
+#[allow(unused_unsafe)]
+fn select_in_word(word: u64, k: u32) -> u32 {
+    // `bmi2` is enabled at compile time
+    unsafe { std::arch::x86_64::_pdep_u64(1 << k, word).trailing_zeros() }
+}
+
+/// Finds the byte that holds the `k`-th set bit of `word` with a broadword
+/// prefix sum, then looks up the bit within that byte
+#[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+fn select_in_word(word: u64, k: u32) -> u32 {
+    const ONES: u64 = 0x0101_0101_0101_0101;
+    const HIGHS: u64 = 0x8080_8080_8080_8080;
+
+    // the number of set bits in each byte
+    let mut counts = word - ((word >> 1) & 0x5555_5555_5555_5555);
+    counts = (counts & 0x3333_3333_3333_3333) + ((counts >> 2) & 0x3333_3333_3333_3333);
+    counts = (counts + (counts >> 4)) & 0x0f0f_0f0f_0f0f_0f0f;
+
+    // the number of set bits in each byte and all of the bytes below it
+    let sums = counts.wrapping_mul(ONES);
+
+    // the high bit of each byte is set if its sum is at most `k`, the sums are
+    // increasing so this counts the bytes below the one that holds the bit
+    let below = (((k as u64 * ONES) | HIGHS) - sums) & HIGHS;
+    let shift = below.count_ones() * 8;
+
+    let rank = ((sums << 8) >> shift) as u8 as u32;
+    let byte = (word >> shift) as u8;
+
+    shift + SELECT_IN_BYTE[byte as usize][(k - rank) as usize] as u32
+}
+
+/// `SELECT_IN_BYTE[byte][k]` is the index of the `k`-th set bit of `byte`
+#[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+static SELECT_IN_BYTE: [[u8; 8]; 256] = {
+    let mut table = [[0; 8]; 256];
+    let mut byte = 0;
+
+    while byte < 256 {
+        let mut bit = 0;
+        let mut k = 0;
+
+        while bit < 8 {
+            if byte >> bit & 1 == 1 {
+                table[byte][k] = bit as u8;
+                k += 1;
+            }
+
+            bit += 1;
+        }
+
+        byte += 1;
+    }
+
+    table
+};
+
+#[test]
+fn select() {
+    let blocks = [
+        1,
+        !0,
+        1 << (BITS - 1),
+        0x5555_5555_5555_5555_u64 as Block,
+        0xf0f0_0000_ff00_0101_u64 as Block,
+        0x8000_0001_0000_8001_u64 as Block,
+    ];
+
+    for &block in &blocks {
+        let ones = (0..BITS).filter(|&i| block & (1 << i) != 0);
+
+        for (k, i) in ones.enumerate() {
+            assert_eq!(select_in_block(block, k as u32), i);
+        }
+    }
+}
+
+#[test]
+fn rank_index() {
+    let mut vec = super::BitVec::new();
+
+    assert_eq!(RankIndex::new(vec.as_slice()).select(0), None);
+    assert_eq!(RankIndex::new(vec.as_slice()).rank(0), 0);
+
+    for i in 0..2000 {
+        vec.push(i % 7 == 3 || (600..1300).contains(&i));
+    }
+
+    for start in [0, 1, 63, 64, 513] {
+        let slice = vec.get(start..).unwrap();
+        let index = RankIndex::new(slice);
+        let ones: Vec<_> = slice.ones().collect();
+
+        assert_eq!(index.count_ones(), ones.len());
+
+        for i in 0..=slice.len() {
+            assert_eq!(index.rank(i), slice.rank(i));
+        }
+
+        for (k, &i) in ones.iter().enumerate() {
+            assert_eq!(index.select(k), Some(i));
+            assert_eq!(slice.select(k), Some(i));
+        }
+
+        assert_eq!(index.select(ones.len()), None);
+    }
+}
@@ -1,4 +1,8 @@
-use super::{get_bit, index_to_slot, set_bit, BitProxy, BitVec};
+use super::rank::{select_in_block, RankIndex};
+use super::{
+    copy_bits, fill_bits, get_bit, index_to_slot, load_bits, mask, store_bits, BitProxy, BitVec,
+    Block, BITS,
+};
 pub(super) use crate::slice::{Seal, SliceIndex, SliceIndexMut};
 
 use std::cell::Cell;
@@ -10,25 +14,25 @@ use std::ops::Deref;
 #[repr(C)]
 #[derive(Clone, Copy)]
 pub struct BitSlice<'a> {
-    ptr: NonNull<u8>,
+    ptr: NonNull<Block>,
     offset: u8,
     len: usize,
-    lt: PhantomData<&'a u8>,
+    lt: PhantomData<&'a Block>,
 }
 
 #[repr(C)]
 pub struct BitSliceMut<'a> {
-    ptr: NonNull<u8>,
+    ptr: NonNull<Block>,
     offset: u8,
     len: usize,
-    lt: PhantomData<&'a mut u8>,
+    lt: PhantomData<&'a mut Block>,
 }
 
-unsafe impl Send for BitSlice<'_> where [u8]: Send + Sync {}
-unsafe impl Sync for BitSlice<'_> where [u8]: Send + Sync {}
+unsafe impl Send for BitSlice<'_> where [Block]: Send + Sync {}
+unsafe impl Sync for BitSlice<'_> where [Block]: Send + Sync {}
 
-unsafe impl Send for BitSliceMut<'_> where [u8]: Send {}
-unsafe impl Sync for BitSliceMut<'_> where [u8]: Send + Sync {}
+unsafe impl Send for BitSliceMut<'_> where [Block]: Send {}
+unsafe impl Sync for BitSliceMut<'_> where [Block]: Send + Sync {}
 
 impl Default for BitSlice<'_> {
     fn default() -> Self {
@@ -60,6 +64,68 @@ impl BitVec {
             lt: PhantomData,
         }
     }
+
+    /// Appends all bits in `other` one block at a time
+    pub fn extend_from_slice(&mut self, other: BitSlice<'_>) {
+        self.grow_blocks(other.len);
+
+        // `grow_blocks` made sure that all the blocks are in bounds
+        unsafe {
+            copy_bits(
+                other.ptr.as_ptr(),
+                other.offset as usize,
+                self.data.as_mut_ptr(),
+                self.len,
+                other.len,
+            );
+        }
+
+        self.len += other.len;
+    }
+
+    /// Appends all bits in `range` one block at a time
+    ///
+    /// Panics if `range` is out of bounds
+    pub fn extend_from_within(&mut self, range: std::ops::Range<usize>) {
+        assert!(
+            range.start <= range.end && range.end <= self.len,
+            "Index is out of bounds!"
+        );
+
+        let len = range.end - range.start;
+
+        self.grow_blocks(len);
+
+        // `grow_blocks` made sure that all the blocks are in bounds, and
+        // the bits being copied do not overlap with the destination
+        unsafe {
+            let ptr = self.data.as_mut_ptr();
+
+            copy_bits(ptr, range.start, ptr, self.len, len);
+        }
+
+        self.len += len;
+    }
+
+    /// Splits the `BitVec` in two at `at`, and returns the bits after `at`
+    ///
+    /// Panics if `at` is out of bounds
+    pub fn split_off(&mut self, at: usize) -> BitVec {
+        let tail = self.get(at..).expect("Index is out of bounds!");
+
+        let mut other = BitVec::new();
+        other.extend_from_slice(tail);
+
+        self.truncate(at);
+
+        other
+    }
+
+    /// Moves all bits in `other` to the end of `self`, leaving `other` empty
+    pub fn append(&mut self, other: &mut BitVec) {
+        self.extend_from_slice(other.as_slice());
+        other.clear();
+    }
 }
 
 impl<'a> BitSliceMut<'a> {
@@ -102,6 +168,148 @@ impl<'a> BitSlice<'a> {
         self.into_iter()
     }
 
+    /// Finds the first bit at or after `from` that is equal to `value`, scanning
+    /// one block at a time
+    pub fn find(self, from: usize, value: bool) -> Option<usize> {
+        let invert = if value { 0 } else { !0 };
+        let mut index = from;
+
+        while index < self.len {
+            let n = (self.len - index).min(BITS) as u8;
+
+            // all bits in `index..index + n` are in bounds
+            let block = unsafe { load_bits(self.ptr.as_ptr(), self.offset as usize + index, n) };
+            let block = (block ^ invert) & mask(n);
+
+            if block != 0 {
+                return Some(index + block.trailing_zeros() as usize);
+            }
+
+            index += n as usize;
+        }
+
+        None
+    }
+
+    /// Finds the last bit before `end` that is equal to `value`, scanning
+    /// one block at a time
+    pub fn rfind(self, end: usize, value: bool) -> Option<usize> {
+        let invert = if value { 0 } else { !0 };
+        let mut end = end.min(self.len);
+
+        while end != 0 {
+            let n = end.min(BITS);
+            let start = end - n;
+
+            // all bits in `start..end` are in bounds
+            let block =
+                unsafe { load_bits(self.ptr.as_ptr(), self.offset as usize + start, n as u8) };
+            let block = (block ^ invert) & mask(n as u8);
+
+            if block != 0 {
+                return Some(start + BITS - 1 - block.leading_zeros() as usize);
+            }
+
+            end = start;
+        }
+
+        None
+    }
+
+    /// Counts the number of bits that are set, one block at a time
+    pub fn count_ones(self) -> usize {
+        let mut count = 0;
+        let mut index = 0;
+
+        while index < self.len {
+            let n = (self.len - index).min(BITS);
+
+            // all bits in `index..index + n` are in bounds
+            let block =
+                unsafe { load_bits(self.ptr.as_ptr(), self.offset as usize + index, n as u8) };
+
+            count += block.count_ones() as usize;
+            index += n;
+        }
+
+        count
+    }
+
+    /// Counts the number of bits that are not set
+    pub fn count_zeros(self) -> usize {
+        self.len - self.count_ones()
+    }
+
+    /// Returns true if all bits are set, stops at the first block with a bit that isn't set
+    pub fn is_all_ones(self) -> bool {
+        self.find(0, false).is_none()
+    }
+
+    /// Returns true if no bits are set, stops at the first block with a bit that is set
+    pub fn is_all_zeros(self) -> bool {
+        self.find(0, true).is_none()
+    }
+
+    /// Counts the number of bits that are set before `index`
+    ///
+    /// Panics if `index > len`
+    pub fn rank(self, index: usize) -> usize {
+        assert!(
+            index <= self.len,
+            "rank index (is {}) should be <= len (is {})",
+            index,
+            self.len
+        );
+
+        unsafe { self.get_unchecked(..index).count_ones() }
+    }
+
+    /// Finds the index of the `k`-th set bit (counting from zero), one block at a time
+    pub fn select(self, mut k: usize) -> Option<usize> {
+        let mut index = 0;
+
+        while index < self.len {
+            let block = self.load_block(index / BITS);
+            let count = block.count_ones() as usize;
+
+            if k < count {
+                return Some(index + select_in_block(block, k as u32));
+            }
+
+            k -= count;
+            index += BITS;
+        }
+
+        None
+    }
+
+    /// Builds an index that answers `rank` in constant time and `select` in logarithmic time
+    pub fn rank_index(self) -> RankIndex<'a> {
+        RankIndex::new(self)
+    }
+
+    /// Reads the `i`-th block of bits, relative to the start of the slice,
+    /// the last block is padded with zeros
+    pub(super) fn load_block(self, i: usize) -> Block {
+        let index = i * BITS;
+        debug_assert!(index < self.len);
+
+        let n = (self.len - index).min(BITS);
+
+        // all bits in `index..index + n` are in bounds
+        unsafe { load_bits(self.ptr.as_ptr(), self.offset as usize + index, n as u8) }
+    }
+
+    /// Returns an iterator over the indices of the bits that are set
+    pub fn ones(self) -> Ones<'a> {
+        Ones {
+            count: self.count_ones(),
+            start: 0,
+            end: self.len,
+            slice: self,
+        }
+    }
+
     pub fn split_at(self, index: usize) -> Option<(Self, Self)> {
         if index <= self.len {
             unsafe { Some(self.split_at_unchecked(index)) }
@@ -244,40 +452,133 @@ impl<'a> BitSliceMut<'a> {
 
         let slot = unsafe { &mut *self.ptr.as_ptr().add(slot) };
 
-        set_bit(slot, offset, value);
+        super::set_bit(slot, offset, value);
     }
 
     pub fn set_all(&mut self, value: bool) {
-        let block_value = if value { !0 } else { 0 };
+        // all bits in the slice are in bounds
+        unsafe {
+            fill_bits(self.ptr.as_ptr(), self.offset as usize, self.len, value);
+        }
+    }
 
-        let (blocks, last) = index_to_slot(self.offset as usize + self.len);
+    pub fn swap(&mut self, a: usize, b: usize) {
+        let (va, vb) = (self.get(a), self.get(b));
+
+        match va.zip(vb) {
+            Some((va, vb)) => {
+                self.set(a, vb);
+                self.set(b, va);
+            }
+            None => panic!("Index is out of bounds!"),
+        }
+    }
+
+    /// Reverses the order of the bits, swapping whole blocks from both ends at a time
+    pub fn reverse(&mut self) {
         let ptr = self.ptr.as_ptr();
+        let offset = self.offset as usize;
+        let mut front = 0;
+        let mut back = self.len;
 
-        let (ptr, blocks) = if self.offset == 0 {
-            (ptr, blocks)
-        } else {
-            unsafe {
-                // first byte
-                for i in self.offset..8 {
-                    set_bit(&mut *ptr, i, value);
-                }
+        // all bits in `front..back` are in bounds
+        unsafe {
+            while back - front >= 2 * BITS {
+                let a = load_bits(ptr, offset + front, BITS as u8);
+                let b = load_bits(ptr, offset + back - BITS, BITS as u8);
+
+                store_bits(ptr, offset + front, BITS as u8, b.reverse_bits());
+                store_bits(ptr, offset + back - BITS, BITS as u8, a.reverse_bits());
 
-                (ptr.add(1), blocks - 1)
+                front += BITS;
+                back -= BITS;
             }
-        };
 
-        unsafe {
-            // last byte
-            let ptr = ptr.add(blocks);
+            while back - front >= 2 {
+                back -= 1;
 
-            for i in 0..last {
-                set_bit(&mut *ptr, i, value);
+                let a = load_bits(ptr, offset + front, 1);
+                let b = load_bits(ptr, offset + back, 1);
+
+                store_bits(ptr, offset + front, 1, b);
+                store_bits(ptr, offset + back, 1, a);
+
+                front += 1;
             }
         }
+    }
+
+    /// Rotates the bits so that the bit at `mid` becomes the first bit
+    ///
+    /// Panics if `mid > len`
+    pub fn rotate_left(&mut self, mid: usize) {
+        assert!(
+            mid <= self.len,
+            "mid (is {}) should be <= len (is {})",
+            mid,
+            self.len
+        );
+
+        let ptr = self.ptr.as_ptr();
+        let offset = self.offset as usize;
+        let mut head = vec![0 as Block; super::block_count(mid)];
+
+        // all bits in the slice are in bounds, and the bits are moved towards the start
+        unsafe {
+            copy_bits(ptr, offset, head.as_mut_ptr(), 0, mid);
+            copy_bits(ptr, offset + mid, ptr, offset, self.len - mid);
+            copy_bits(head.as_ptr(), 0, ptr, offset + self.len - mid, mid);
+        }
+    }
+
+    /// Copies all of the bits from `src`, one block at a time
+    ///
+    /// Panics if the slices have different lengths
+    pub fn copy_from_slice(&mut self, src: BitSlice<'_>) {
+        assert_eq!(
+            self.len, src.len,
+            "destination and source slices have different lengths"
+        );
+
+        // all bits in both slices are in bounds, and a mutable slice can't
+        // overlap with a shared slice
+        unsafe {
+            copy_bits(
+                src.ptr.as_ptr(),
+                src.offset as usize,
+                self.ptr.as_ptr(),
+                self.offset as usize,
+                self.len,
+            );
+        }
+    }
+
+    /// Swaps all of the bits with the bits in `other`, one block at a time
+    ///
+    /// Panics if the slices have different lengths
+    pub fn swap_with_slice(&mut self, other: &mut BitSliceMut<'_>) {
+        assert_eq!(
+            self.len, other.len,
+            "destination and source slices have different lengths"
+        );
+
+        let (a_ptr, a_offset) = (self.ptr.as_ptr(), self.offset as usize);
+        let (b_ptr, b_offset) = (other.ptr.as_ptr(), other.offset as usize);
+        let mut index = 0;
 
+        // all bits in both slices are in bounds, and mutable slices can't overlap
         unsafe {
-            // middle bytes
-            std::ptr::write_bytes(ptr, block_value, blocks);
+            while index < self.len {
+                let n = (self.len - index).min(BITS) as u8;
+
+                let a = load_bits(a_ptr, a_offset + index, n);
+                let b = load_bits(b_ptr, b_offset + index, n);
+
+                store_bits(a_ptr, a_offset + index, n, b);
+                store_bits(b_ptr, b_offset + index, n, a);
+
+                index += n as usize;
+            }
         }
     }
 
@@ -503,7 +804,7 @@ impl<'a> SliceIndex<BitSlice<'a>> for Range<usize> {
     type Output = BitSlice<'a>;
 
     fn check(&self, slice: &BitSlice) -> bool {
-        self.start < self.end && self.end <= slice.len
+        self.start <= self.end && self.end <= slice.len
     }
 
     unsafe fn get_unchecked(self, slice: BitSlice<'a>) -> Self::Output {
@@ -517,7 +818,7 @@ impl<'a> SliceIndexMut<BitSliceMut<'a>> for Range<usize> {
     type Output = BitSliceMut<'a>;
 
     fn check_mut(&self, slice: &BitSliceMut) -> bool {
-        self.start < self.end && self.end <= slice.len
+        self.start <= self.end && self.end <= slice.len
     }
 
     unsafe fn get_unchecked_mut(self, slice: BitSliceMut<'a>) -> Self::Output {
@@ -533,7 +834,7 @@ impl<'a> SliceIndex<BitSlice<'a>> for RangeInclusive<usize> {
     type Output = BitSlice<'a>;
 
     fn check(&self, slice: &BitSlice) -> bool {
-        self.start() <= self.end() && *self.end() <= slice.len
+        self.start() <= self.end() && *self.end() < slice.len
     }
 
     unsafe fn get_unchecked(self, slice: BitSlice<'a>) -> Self::Output {
@@ -549,7 +850,7 @@ impl<'a> SliceIndexMut<BitSliceMut<'a>> for RangeInclusive<usize> {
     type Output = BitSliceMut<'a>;
 
     fn check_mut(&self, slice: &BitSliceMut) -> bool {
-        self.start() <= self.end() && *self.end() <= slice.len
+        self.start() <= self.end() && *self.end() < slice.len
     }
 
     unsafe fn get_unchecked_mut(self, slice: BitSliceMut<'a>) -> Self::Output {
@@ -560,10 +861,10 @@ impl<'a> SliceIndexMut<BitSliceMut<'a>> for RangeInclusive<usize> {
 }
 
 #[cfg(test)]
-fn from_bytes(slice: &mut [u8], range: std::ops::Range<usize>) -> BitSliceMut<'_> {
-    let offset = (range.start & 0b0111) as u8;
+fn from_blocks(slice: &mut [Block], range: std::ops::Range<usize>) -> BitSliceMut<'_> {
+    let offset = (range.start % BITS) as u8;
     let len = range.end - range.start;
-    let slice = &mut slice[range.start >> 3..(range.end + 7) >> 3];
+    let slice = &mut slice[range.start / BITS..];
 
     BitSliceMut {
         ptr: NonNull::from(slice).cast(),
@@ -573,6 +874,51 @@ fn from_bytes(slice: &mut [u8], range: std::ops::Range<usize>) -> BitSliceMut<'_
     }
 }
 
+/// An iterator over the indices of the bits that are set, skips blocks that
+/// have no bits set
+#[derive(Clone, Copy)]
+pub struct Ones<'a> {
+    slice: BitSlice<'a>,
+    start: usize,
+    end: usize,
+    count: usize,
+}
+
+impl Iterator for Ones<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.count = self.count.checked_sub(1)?;
+
+        // there is at least one set bit in `start..end`, so this will
+        // find a bit before `end`
+        let index = unsafe { self.slice.find(self.start, true).unwrap_unchecked() };
+        self.start = index + 1;
+
+        Some(index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.count, Some(self.count))
+    }
+}
+
+impl DoubleEndedIterator for Ones<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.count = self.count.checked_sub(1)?;
+
+        // there is at least one set bit in `start..end`, so this will
+        // find a bit after `start`
+        let index = unsafe { self.slice.rfind(self.end, true).unwrap_unchecked() };
+        self.end = index;
+
+        Some(index)
+    }
+}
+
+impl ExactSizeIterator for Ones<'_> {}
+impl std::iter::FusedIterator for Ones<'_> {}
+
 pub struct Iter<'a> {
     slice: BitSlice<'a>,
 }
@@ -657,7 +1003,7 @@ impl<'a> Iterator for IterMut<'a> {
     type Item = BitProxy<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let slice = std::mem::replace(&mut self.slice, Default::default());
+        let slice = std::mem::take(&mut self.slice);
 
         let (next, rest) = slice.split_first_mut().ok()?;
 
@@ -667,7 +1013,7 @@ impl<'a> Iterator for IterMut<'a> {
     }
 
     fn nth(&mut self, n: usize) -> Option<Self::Item> {
-        let slice = std::mem::replace(&mut self.slice, Default::default());
+        let slice = std::mem::take(&mut self.slice);
 
         self.slice = slice.into_get_mut(n..)?;
 
@@ -681,7 +1027,7 @@ impl<'a> Iterator for IterMut<'a> {
 
 impl<'a> DoubleEndedIterator for IterMut<'a> {
     fn next_back(&mut self) -> Option<Self::Item> {
-        let slice = std::mem::replace(&mut self.slice, Default::default());
+        let slice = std::mem::take(&mut self.slice);
 
         let (rest, next) = slice.split_last_mut().ok()?;
 
@@ -692,7 +1038,7 @@ impl<'a> DoubleEndedIterator for IterMut<'a> {
 
     #[cfg(feature = "nightly")]
     fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
-        let slice = std::mem::replace(&mut self.slice, Default::default());
+        let slice = std::mem::take(&mut self.slice);
 
         let index = slice.len.checked_sub(n)?;
 
@@ -725,18 +1071,146 @@ impl<'a> IntoIterator for BitSliceMut<'a> {
 
 #[test]
 fn slice() {
-    let mut a = [0u8; 16];
+    let mut a = [0 as Block; 128 / BITS];
+
+    from_blocks(&mut a, 4..28).set_all(true);
 
-    from_bytes(&mut a, 4..28).set_all(true);
+    from_blocks(&mut a, 32..64).set_all(true);
 
-    from_bytes(&mut a, 32..64).set_all(true);
+    from_blocks(&mut a, 64..92).set_all(true);
 
-    from_bytes(&mut a, 64..92).set_all(true);
+    from_blocks(&mut a, 100..128).set_all(true);
 
-    from_bytes(&mut a, 100..128).set_all(true);
+    let bytes = a
+        .iter()
+        .flat_map(|block| block.to_le_bytes().to_vec())
+        .collect::<Vec<_>>();
 
     assert_eq!(
-        a,
+        bytes,
         [240, 255, 255, 15, 255, 255, 255, 255, 255, 255, 255, 15, 240, 255, 255, 255]
     );
 }
+
+#[test]
+fn slice_within_block() {
+    let mut a = [0 as Block; 128 / BITS];
+
+    from_blocks(&mut a, 4..28).set_all(true);
+
+    from_blocks(&mut a, 1..3).set_all(true);
+
+    from_blocks(&mut a, 5..7).set_all(false);
+
+    assert_eq!(a[0].to_le_bytes()[..4], [150, 255, 255, 15]);
+    assert_eq!(a[1..], [0; 128 / BITS - 1]);
+}
+
+#[test]
+fn splice() {
+    let model = (0..100)
+        .map(|i| i % 3 == 0 || i % 7 == 0)
+        .collect::<Vec<_>>();
+
+    for &at in &[0, 3, 8, 13, 64, 99, 100] {
+        let mut vec = BitVec::new();
+
+        for &bit in &model {
+            vec.push(bit);
+        }
+
+        let mut tail = vec.split_off(at);
+
+        assert!(vec.iter().eq(model[..at].iter().copied()));
+        assert!(tail.iter().eq(model[at..].iter().copied()));
+
+        vec.extend_from_within(at / 3..at / 2);
+
+        let mut expected = model[..at].to_vec();
+        expected.extend_from_within(at / 3..at / 2);
+
+        assert!(vec.iter().eq(expected.iter().copied()));
+
+        vec.append(&mut tail);
+        expected.extend_from_slice(&model[at..]);
+
+        assert!(vec.iter().eq(expected.iter().copied()));
+        assert!(tail.is_empty());
+    }
+}
+
+#[test]
+fn splice_across_blocks() {
+    let model = (0..300)
+        .map(|i| i % 3 == 0 || i % 7 == 0)
+        .collect::<Vec<_>>();
+
+    // the bits past `len` must stay zeroed
+    fn check_padding(vec: &BitVec) {
+        let (slot, offset) = index_to_slot(vec.len);
+
+        if offset != 0 {
+            assert_eq!(vec.data[slot] & !mask(offset), 0);
+        }
+    }
+
+    for &at in &[0, 3, 8, 13, 63, 64, 65, 99, 100, 127, 128, 200, 299, 300] {
+        let mut vec = BitVec::new();
+
+        for &bit in &model {
+            vec.push(bit);
+        }
+
+        let mut tail = vec.split_off(at);
+
+        assert!(vec.iter().eq(model[..at].iter().copied()));
+        assert!(tail.iter().eq(model[at..].iter().copied()));
+        check_padding(&vec);
+        check_padding(&tail);
+
+        vec.extend_from_within(at / 3..at / 2 + at / 5);
+
+        let mut expected = model[..at].to_vec();
+        expected.extend_from_within(at / 3..at / 2 + at / 5);
+
+        assert!(vec.iter().eq(expected.iter().copied()));
+        check_padding(&vec);
+
+        vec.append(&mut tail);
+        expected.extend_from_slice(&model[at..]);
+
+        assert!(vec.iter().eq(expected.iter().copied()));
+        assert!(tail.is_empty());
+        check_padding(&vec);
+    }
+}
+
+#[test]
+fn count() {
+    let mut a = [0 as Block; 256 / BITS];
+
+    from_blocks(&mut a, 3..90).set_all(true);
+    from_blocks(&mut a, 40..45).set_all(false);
+    from_blocks(&mut a, 130..250).set_all(true);
+
+    let model =
+        |i: usize| (3..90).contains(&i) && !(40..45).contains(&i) || (130..250).contains(&i);
+
+    for &(start, end) in &[
+        (0, 256),
+        (1, 255),
+        (5, 70),
+        (64, 128),
+        (90, 130),
+        (131, 249),
+        (7, 7),
+    ] {
+        let slice = from_blocks(&mut a, start..end).into_slice();
+        let ones = (start..end).filter(|&i| model(i)).count();
+
+        assert_eq!(slice.count_ones(), ones);
+        assert_eq!(slice.count_zeros(), end - start - ones);
+        assert_eq!(slice.is_all_ones(), ones == end - start);
+        assert_eq!(slice.is_all_zeros(), ones == 0);
+    }
+}
@@ -1,7 +1,4 @@
-#![cfg_attr(
-    feature = "nightly",
-    feature(specialization, try_trait, slice_from_raw_parts, const_fn)
-)]
+#![cfg_attr(feature = "nightly", feature(specialization, try_trait_v2))]
 #![allow(clippy::option_option)]
 // #![forbid(missing_docs)]
 
@@ -12,7 +9,9 @@ A space optimized version of `Vec<Option<T>>` that stores the discriminant seper
 
 ## Feature flags
 
-`nightly` - This turns on a few optimizations (makes `Clone`ing `Copy` elements much cheaper) and extends `try_fold` and `try_for_each` to work with all `Try` types. Finally, this also allows the `iterator.nth_back(n)` methods to be used.
+`nightly` - This specializes `Clone` for `Copy` elements, so that `clone` and `clone_from` copy the data with a `memcpy` automatically, and extends `try_fold` and `try_for_each` to work with all `Try` types. Finally, this also allows the `iterator.nth_back(n)` methods to be used.
+
+Cheap copies don't need nightly, `clone_copy` and `clone_from_copy` do the same `memcpy` on stable, the `nightly` feature only makes `Clone` use them.
 
 ## Pros
 
@@ -24,9 +23,34 @@ A space optimized version of `Vec<Option<T>>` that stores the discriminant seper
 ## Cons
 
 * 2 allocations, instead of a single allocation
-* Cannot remove elements from the middle of the vector
 * Cannot work on the option's directly
 
+## Collecting
+
+`VecOption<T>` can be collected from an iterator over `Option<T>`, and also from an iterator over `T`, where every element is `Some`.
+Because of this, collecting options into a `VecOption<_>` is ambiguous, and the element type has to be written out.
+
+```rust compile_fail
+# use vec_option::VecOption;
+let options = vec![Some(1), None, Some(3)];
+
+// error[E0283]: type annotations needed
+let vec: VecOption<_> = options.into_iter().collect();
+```
+
+```rust
+# use vec_option::VecOption;
+let options = vec![Some(1), None, Some(3)];
+
+let vec: VecOption<i32> = options.into_iter().collect();
+
+assert_eq!(vec, [Some(1), None, Some(3)]);
+
+let vec: VecOption<i32> = (0..3).collect();
+
+assert_eq!(vec, [Some(0), Some(1), Some(2)]);
+```
+
 ## Example
 
 Just like a normal vector, you can push and pop elements from the end of the vector
@@ -107,16 +131,53 @@ But due to the limitations imposed by spliting the representation of the vector,
 `&Option<T>`/`&mut Option<T>` outside of a closure.
 In fact, you can't get an `&Option<T>` at all, it would be fairly useless, as the only thing you can really do with it is convert it to a `Option<&T>`. But `&mut Option<T>` is usefull, so there are a handful of functions that allow you to operate with them.
 
+```rust
+# use vec_option::VecOption;
+let mut vec = VecOption::from(vec![0, 1, 2, 3, 4]);
+
+// This one allows you to edit a single value however you want, and the updates will
+// be reflected once the closure returns. If the closure panics, then it is as if you took the
+// option out of the vector.
+vec.with_mut(1, |element: &mut Option<i32>| {
+    *element = None;
+});
+
+assert_eq!(vec, [Some(0), None, Some(2), Some(3), Some(4)]);
+```
+
 These functions below are like the corrosponding functions in `Iterator`, they iterate over the vector and allow you to do stuff based on which one you call. The only difference is that you get to operate on `&mut Option<T>` directly. Again, if the closure panics, it will be as if you took the value out of the vector.
 
-```rust ignore
-vec.try_fold(...);
+```rust
+# use vec_option::VecOption;
+let mut vec = VecOption::from(vec![0, 1, 2, 3, 4]);
+
+let sum = vec.fold(0, |acc, element| acc + element.take().unwrap_or(0));
+assert_eq!(sum, 10);
+assert_eq!(vec, [None, None, None, None, None]);
+
+let res: Result<(), usize> = vec.try_fold(0, |count, element| {
+    if count == 3 {
+        return Err(count);
+    }
+
+    *element = Some(count);
+
+    Ok(count + 1)
+}).map(drop);
+
+assert_eq!(res, Err(3));
+assert_eq!(vec, [Some(0), Some(1), Some(2), None, None]);
 
-vec.fold(...);
+vec.for_each(|element| if element.is_none() { *element = Some(10) });
+assert_eq!(vec, [Some(0), Some(1), Some(2), Some(10), Some(10)]);
 
-vec.try_for_each(...);
+let res = vec.try_for_each(|element| match element {
+    Some(10) => Err(()),
+    _ => Ok(*element = None),
+});
 
-vec.for_each(...);
+assert_eq!(res, Err(()));
+assert_eq!(vec, [None, None, None, Some(10), Some(10)]);
 ```
 
 But because of these limitations, you can very quickly fill up your vector with `None` and set all of the elements in your vector to `None`! This can compile down to just a `memset` if your types don't have drop glue!
@@ -139,43 +200,23 @@ assert_eq!(vec, [None, None, None, None, None, None, None, None, None, None]);
 
 mod bit_vec;
 
-use bit_vec::BitVec;
-
-use std::mem::MaybeUninit;
-use std::ops::{Deref, DerefMut};
-
-pub mod slice;
+#[cfg(not(feature = "nightly"))]
+mod try_trait;
 
-/// # Safety
-///
-/// This code must never be run
-#[cold]
-unsafe fn unreachable_unchecked() -> ! {
-    use std::hint::unreachable_unchecked;
-
-    debug_assert!(false, "unreachable");
-    unreachable_unchecked()
-}
+#[cfg(not(feature = "nightly"))]
+pub use try_trait::Try;
 
-trait UnwrapUnchecked {
-    type Output;
+#[cfg(feature = "nightly")]
+pub use std::ops::Try;
 
-    /// # Safety
-    ///
-    /// The Option<T> must be in the `Some` variant
-    unsafe fn unwrap_unchecked(self) -> Self::Output;
-}
+use bit_vec::BitVec;
 
-impl<T> UnwrapUnchecked for Option<T> {
-    type Output = T;
+use std::collections::TryReserveError;
+use std::convert::TryFrom;
+use std::mem::{ManuallyDrop, MaybeUninit};
+use std::ops::{Bound, Deref, DerefMut, Range, RangeBounds};
 
-    unsafe fn unwrap_unchecked(self) -> Self::Output {
-        match self {
-            Some(value) => value,
-            None => unreachable_unchecked(),
-        }
-    }
-}
+pub mod slice;
 
 /// # Safety
 ///
@@ -190,6 +231,42 @@ unsafe fn from_raw_parts<T>(flag: bool, data: MaybeUninit<T>) -> Option<T> {
     }
 }
 
+/// Converts `range` to a `Range<usize>` that is in bounds of a vector of length `len`
+///
+/// Panics if the range is decreasing or out of bounds
+fn to_range<R: RangeBounds<usize>>(range: R, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start
+            .checked_add(1)
+            .expect("attempted to index slice from after maximum usize"),
+        Bound::Unbounded => 0,
+    };
+
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end
+            .checked_add(1)
+            .expect("attempted to index slice up to maximum usize"),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+
+    assert!(
+        start <= end,
+        "slice index starts at {} but ends at {}",
+        start,
+        end
+    );
+    assert!(
+        end <= len,
+        "range end index {} out of range for slice of length {}",
+        end,
+        len
+    );
+
+    start..end
+}
+
 /// A space optimized version of `Vec<Option<T>>` that stores the discriminant seperately
 ///
 /// See crate-level docs for more information
@@ -200,15 +277,85 @@ pub struct VecOption<T> {
 }
 
 /// The capacity information of the given `VecOption<T>`
+///
+/// Both capacities are measured in elements
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
 pub struct CapacityInfo {
-    /// The capacity of the data vector that holds `T`s
+    /// The number of `T`s the data vector can hold without reallocating
     pub data: usize,
 
-    /// The capacity of the `BitVec` that holds the discriminants
+    /// The number of discriminants the `BitVec` can hold without reallocating
     pub flag: usize,
+}
+
+/// Writes elements into the spare capacity of a `VecOption<T>`,
+/// and pushes their flags one block at a time
+///
+/// The elements that were written are committed on drop, even if the
+/// code producing them panics
+struct BlockWriter<'a, T> {
+    vec: &'a mut VecOption<T>,
+    block: bit_vec::Block,
+    len: u8,
+}
+
+impl<'a, T> BlockWriter<'a, T> {
+    fn new(vec: &'a mut VecOption<T>) -> Self {
+        Self {
+            vec,
+            block: 0,
+            len: 0,
+        }
+    }
 
-    _priv: (),
+    /// # Safety
+    ///
+    /// There must be enough spare capacity in `vec.data` for another element
+    unsafe fn write(&mut self, value: Option<T>) {
+        let index = self.vec.data.len() + self.len as usize;
+
+        debug_assert!(index < self.vec.data.capacity());
+
+        if let Some(value) = value {
+            self.vec
+                .data
+                .as_mut_ptr()
+                .add(index)
+                .write(MaybeUninit::new(value));
+            self.block |= 1 << self.len;
+        }
+
+        self.len += 1;
+
+        if self.len as usize == bit_vec::BITS {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.len == 0 {
+            return;
+        }
+
+        let len = self.vec.data.len() + self.len as usize;
+
+        self.vec.flag.push_block(self.block, self.len);
+
+        // all elements up to `len` were written and their flags were pushed
+        unsafe {
+            self.vec.data.set_len(len);
+        }
+
+        self.block = 0;
+        self.len = 0;
+    }
+}
+
+impl<T> Drop for BlockWriter<'_, T> {
+    fn drop(&mut self) {
+        self.flush();
+    }
 }
 
 impl<T> Default for VecOption<T> {
@@ -275,6 +422,109 @@ impl<T: fmt::Debug> fmt::Debug for OptionProxy<'_, T> {
     }
 }
 
+/// A lazy proxy to an option in a `VecOption<T>`
+///
+/// Unlike `OptionProxy`, this works on the element in place, so the value is only moved
+/// when the option is reassigned. Use `into_proxy` to get full access to the `Option<T>`
+pub struct LazyOptionProxy<'a, T> {
+    data: &'a mut MaybeUninit<T>,
+    flag: bit_vec::BitProxy<'a>,
+}
+
+impl<'a, T> LazyOptionProxy<'a, T> {
+    unsafe fn new(flag: bit_vec::BitProxy<'a>, data: &'a mut MaybeUninit<T>) -> Self {
+        Self { data, flag }
+    }
+
+    /// Returns true if the option is `Some`
+    pub fn is_some(&self) -> bool {
+        *self.flag
+    }
+
+    /// Returns true if the option is `None`
+    pub fn is_none(&self) -> bool {
+        !*self.flag
+    }
+
+    /// Returns a reference to the value in the vector
+    pub fn as_ref(&self) -> Option<&T> {
+        // the flag is set, so the data is initialized
+        if *self.flag {
+            unsafe { Some(self.data.assume_init_ref()) }
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the value in the vector
+    pub fn as_mut(&mut self) -> Option<&mut T> {
+        // the flag is set, so the data is initialized
+        if *self.flag {
+            unsafe { Some(self.data.assume_init_mut()) }
+        } else {
+            None
+        }
+    }
+
+    /// Converts into a mutable reference to the value in the vector
+    pub fn into_mut(self) -> Option<&'a mut T> {
+        // the flag is set, so the data is initialized
+        if *self.flag {
+            unsafe { Some(self.data.assume_init_mut()) }
+        } else {
+            None
+        }
+    }
+
+    /// Replaces the option with `value`, and returns the old value
+    pub fn replace<O: Into<Option<T>>>(&mut self, value: O) -> Option<T> {
+        let value = value.into();
+
+        // the flag is set, so the data is initialized,
+        // and it is cleared before the data is moved out
+        let old = if *self.flag {
+            unsafe { Some(self.data.as_ptr().read()) }
+        } else {
+            None
+        };
+
+        *self.flag = value.is_some();
+
+        if let Some(value) = value {
+            self.data.write(value);
+        }
+
+        // keep the vector up to date, in case this proxy is leaked
+        self.flag.flush();
+
+        old
+    }
+
+    /// Takes the value out of the option, leaving `None` in its place
+    pub fn take(&mut self) -> Option<T> {
+        self.replace(None)
+    }
+
+    /// Inserts `value` into the option, then returns a mutable reference to it
+    pub fn insert(&mut self, value: T) -> &mut T {
+        self.replace(value);
+
+        // the value was just written
+        unsafe { self.data.assume_init_mut() }
+    }
+
+    /// Moves the value out of the vector to give access to the whole `Option<T>`
+    pub fn into_proxy(self) -> OptionProxy<'a, T> {
+        unsafe { OptionProxy::new(self.flag, self.data) }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for LazyOptionProxy<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
+
 impl<T> VecOption<T> {
     /// Creates an empty vector, does not allocate
     pub fn new() -> Self {
@@ -284,6 +534,41 @@ impl<T> VecOption<T> {
         }
     }
 
+    /// Creates a vector with `len` `None`s
+    ///
+    /// The discriminants are allocated with `alloc_zeroed`, and the data is left uninitialized,
+    /// so the memory isn't touched until it is used
+    pub fn with_len_none(len: usize) -> Self {
+        let mut data = Vec::with_capacity(len);
+
+        // Because this is a Vec<MaybeUninit<T>>, we only need to
+        // guarantee that we have enough space in the allocatation
+        // for `set_len` to be safe, and that was done with `with_capacity`
+        unsafe {
+            data.set_len(len);
+        }
+
+        Self {
+            data,
+            flag: BitVec::zeroed(len),
+        }
+    }
+
+    /// Creates a vector of length `len`, where the element at index `i` is `f(i)`
+    pub fn from_fn<F: FnMut(usize) -> Option<T>>(len: usize, mut f: F) -> Self {
+        let mut vec = Self::with_capacity(len);
+        let mut writer = BlockWriter::new(&mut vec);
+
+        for i in 0..len {
+            // there is enough space because of `with_capacity`
+            unsafe { writer.write(f(i)) }
+        }
+
+        drop(writer);
+
+        vec
+    }
+
     /// Creates an empty vector
     ///
     /// allocates at least `cap` elements of space
@@ -310,6 +595,39 @@ impl<T> VecOption<T> {
         self.flag.reserve_exact(amount);
     }
 
+    /// tries to reserve at least `amount` elements, returns an error
+    /// if the capacity overflows or the allocator reports a failure
+    ///
+    /// if there is already enough space, this does nothing
+    pub fn try_reserve(&mut self, amount: usize) -> Result<(), TryReserveError> {
+        self.data.try_reserve(amount)?;
+        self.flag.try_reserve(amount)
+    }
+
+    /// tries to reserve exactly `amount` elements, returns an error
+    /// if the capacity overflows or the allocator reports a failure
+    ///
+    /// if there is already enough space, this does nothing
+    pub fn try_reserve_exact(&mut self, amount: usize) -> Result<(), TryReserveError> {
+        self.data.try_reserve_exact(amount)?;
+        self.flag.try_reserve_exact(amount)
+    }
+
+    /// shrinks the capacity of the vector as much as possible
+    pub fn shrink_to_fit(&mut self) {
+        self.data.shrink_to_fit();
+        self.flag.shrink_to_fit();
+    }
+
+    /// shrinks the capacity of the vector, while keeping space for at least
+    /// `min_capacity` elements
+    ///
+    /// if the capacity is already less than `min_capacity`, this does nothing
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.data.shrink_to(min_capacity);
+        self.flag.shrink_to(min_capacity);
+    }
+
     /// The length of this vector
     pub fn len(&self) -> usize {
         self.data.len()
@@ -319,8 +637,7 @@ impl<T> VecOption<T> {
     pub fn capacity(&self) -> CapacityInfo {
         CapacityInfo {
             data: self.data.capacity(),
-            flag: self.flag.alloc_info().cap,
-            _priv: (),
+            flag: self.flag.capacity(),
         }
     }
 
@@ -329,6 +646,14 @@ impl<T> VecOption<T> {
         self.data.is_empty()
     }
 
+    /// Splits the vector into its data and flags without dropping any of the elements
+    fn into_parts(self) -> (Vec<MaybeUninit<T>>, BitVec) {
+        let vec = ManuallyDrop::new(self);
+
+        // `vec` will not be dropped, so it is fine to move out the data and flag
+        unsafe { (std::ptr::read(&vec.data), std::ptr::read(&vec.flag)) }
+    }
+
     /// Put a value at the end of the vector
     ///
     /// Reallocates if there is not enough space
@@ -364,7 +689,103 @@ impl<T> VecOption<T> {
         }
     }
 
-    /// Returns a proxy to a mutable reference to the element at `index` or None if out of bounds.
+    /// Inserts `value` at `index`, shifting all elements after it to the right
+    ///
+    /// Panics if `index > len`
+    pub fn insert<V: Into<Option<T>>>(&mut self, index: usize, value: V) {
+        let len = self.len();
+
+        assert!(
+            index <= len,
+            "insertion index (is {}) should be <= len (is {})",
+            index,
+            len
+        );
+
+        match value.into() {
+            Some(value) => {
+                self.data.insert(index, MaybeUninit::new(value));
+                self.flag.insert(index, true);
+            }
+            None => {
+                self.data.insert(index, MaybeUninit::uninit());
+                self.flag.insert(index, false);
+            }
+        }
+    }
+
+    /// Removes and returns the element at `index`, shifting all elements after it to the left
+    ///
+    /// Panics if `index` is out of bounds
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        let len = self.len();
+
+        assert!(
+            index < len,
+            "removal index (is {}) should be < len (is {})",
+            index,
+            len
+        );
+
+        let data = self.data.remove(index);
+        let flag = self.flag.remove(index);
+
+        // The flag and data are a pair, (same index)
+        unsafe { from_raw_parts(flag, data) }
+    }
+
+    /// Removes and returns the element at `index`, and replaces it with the last element
+    ///
+    /// This does not preserve ordering, but is O(1)
+    ///
+    /// Panics if `index` is out of bounds
+    pub fn swap_remove(&mut self, index: usize) -> Option<T> {
+        let len = self.len();
+
+        assert!(
+            index < len,
+            "swap_remove index (is {}) should be < len (is {})",
+            index,
+            len
+        );
+
+        let data = self.data.swap_remove(index);
+        let flag = self.flag.swap_remove(index);
+
+        // The flag and data are a pair, (same index)
+        unsafe { from_raw_parts(flag, data) }
+    }
+
+    /// Splits the vector in two at `at`, and returns the elements after `at`
+    ///
+    /// Panics if `at > len`
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let len = self.len();
+
+        assert!(
+            at <= len,
+            "`at` split index (is {}) should be <= len (is {})",
+            at,
+            len
+        );
+
+        Self {
+            data: self.data.split_off(at),
+            flag: self.flag.split_off(at),
+        }
+    }
+
+    /// Moves all elements in `other` to the end of `self`, leaving `other` empty
+    pub fn append(&mut self, other: &mut Self) {
+        self.data.append(&mut other.data);
+        self.flag.append(&mut other.flag);
+    }
+
+    /// Returns a proxy to a mutable reference to the element at `index`
+    ///
+    /// # Safety
+    ///
+    /// `index` must be in bounds
     pub unsafe fn get_unchecked_mut<'a, I: slice::SliceIndexMut<slice::SliceMut<'a, T>>>(
         &'a mut self,
         index: I,
@@ -380,7 +801,23 @@ impl<T> VecOption<T> {
         self.as_mut_slice().into_get_mut(index)
     }
 
-    /// Returns a reference to the element at `index` or None if out of bounds.
+    /// Returns a mutable reference to the element at `index` if it is `Some`,
+    /// this points directly into the vector, so the value is not moved
+    pub fn get_some_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.as_mut_slice().into_some_mut(index)
+    }
+
+    /// Returns a lazy proxy to the element at `index` or None if out of bounds,
+    /// the value is only moved if the option is reassigned
+    pub fn get_lazy_mut(&mut self, index: usize) -> Option<LazyOptionProxy<'_, T>> {
+        self.as_mut_slice().into_lazy_mut(index)
+    }
+
+    /// Returns a reference to the element at `index`
+    ///
+    /// # Safety
+    ///
+    /// `index` must be in bounds
     pub unsafe fn get_unchecked<'a, I: slice::SliceIndex<slice::Slice<'a, T>>>(
         &'a self,
         index: I,
@@ -431,24 +868,16 @@ impl<T> VecOption<T> {
         }
 
         if std::mem::needs_drop::<T>() {
-            for (i, data) in self.data.iter_mut().enumerate().skip(len) {
-                unsafe {
-                    // index corrosponds to the index of a data, so it is valid
-                    if self.flag.get_unchecked(i) {
-                        self.flag.set(i, false);
-
-                        // data is initialized, checked by flag
-                        data.as_mut_ptr().drop_in_place()
-                    }
-                }
-            }
+            // `len` is less than the length of the vector
+            unsafe { self.as_mut_slice().into_get_unchecked_mut(len..).set_none() }
         }
 
         // decreasing the length is always fine
         unsafe {
             self.data.set_len(len);
-            self.flag.set_len(len);
         }
+
+        self.flag.truncate(len);
     }
 
     /// Clears the vector
@@ -459,17 +888,96 @@ impl<T> VecOption<T> {
     /// Sets all of the elements in the vector to `None` and drops
     /// all values in the closure
     pub fn set_all_none(&mut self) {
-        if std::mem::needs_drop::<T>() {
-            for (i, data) in self.data.iter_mut().enumerate() {
+        self.as_mut_slice().set_none();
+    }
+
+    /// Resizes the vector to `new_len`, either by truncating it or
+    /// by filling the new space with clones of `value`
+    pub fn resize<V: Into<Option<T>>>(&mut self, new_len: usize, value: V)
+    where
+        T: Clone,
+    {
+        let len = self.len();
+
+        if new_len <= len {
+            return self.truncate(new_len);
+        }
+
+        let additional = new_len - len;
+
+        match value.into() {
+            None => self.extend_none(additional),
+            Some(value) => {
+                self.reserve(additional);
+
+                let mut writer = BlockWriter::new(self);
+
+                // there is enough space because of the `reserve` above
                 unsafe {
-                    if self.flag.get_unchecked(i) {
-                        self.flag.set(i, false);
-                        data.as_mut_ptr().drop_in_place()
+                    for _ in 1..additional {
+                        writer.write(Some(value.clone()));
                     }
+
+                    writer.write(Some(value));
                 }
             }
-        } else {
-            self.flag.set_all(false);
+        }
+    }
+
+    /// Resizes the vector to `new_len`, either by truncating it or
+    /// by filling the new space with values returned from `f`
+    pub fn resize_with<F: FnMut() -> Option<T>>(&mut self, new_len: usize, mut f: F) {
+        let len = self.len();
+
+        if new_len <= len {
+            return self.truncate(new_len);
+        }
+
+        let additional = new_len - len;
+
+        self.reserve(additional);
+
+        let mut writer = BlockWriter::new(self);
+
+        for _ in 0..additional {
+            // there is enough space because of the `reserve` above
+            unsafe { writer.write(f()) }
+        }
+    }
+
+    /// Writes the elements of `iter` straight into the spare capacity,
+    /// only reserving more space when the lower bound of the size hint is exceeded
+    fn extend_iter<I: Iterator<Item = Option<T>>>(&mut self, mut iter: I) {
+        while let Some(value) = iter.next() {
+            self.reserve(iter.size_hint().0.saturating_add(1));
+
+            let spare = self.data.capacity() - self.data.len();
+            let mut writer = BlockWriter::new(self);
+
+            // there is enough space for `value` and the next `spare - 1` elements
+            // because of the `reserve` above
+            unsafe {
+                writer.write(value);
+
+                for value in iter.by_ref().take(spare - 1) {
+                    writer.write(value);
+                }
+            }
+        }
+    }
+
+    /// Clones and appends all of the elements in `slice`
+    pub fn extend_from_slice(&mut self, slice: &[T])
+    where
+        T: Clone,
+    {
+        self.reserve(slice.len());
+
+        let mut writer = BlockWriter::new(self);
+
+        for value in slice {
+            // there is enough space because of the `reserve` above
+            unsafe { writer.write(Some(value.clone())) }
         }
     }
 
@@ -489,7 +997,337 @@ impl<T> VecOption<T> {
         }
     }
 
-    /// returns an iterator over references to the elements in the vector
+    /// Calls `f` with a mutable reference to the element at `index`,
+    /// returns `None` if `index` is out of bounds
+    ///
+    /// If `f` panics, the element will be set to `None`
+    pub fn with_mut<R, F: FnOnce(&mut Option<T>) -> R>(&mut self, index: usize, f: F) -> Option<R> {
+        self.as_mut_slice().with_mut(index, f)
+    }
+
+    /// Folds every element into an accumulator by applying an operation,
+    /// returning the final result, stopping early if `f` short-circuits
+    ///
+    /// If `f` panics, the element it was called with will be set to `None`
+    pub fn try_fold<A, R, F>(&mut self, init: A, f: F) -> R
+    where
+        F: FnMut(A, &mut Option<T>) -> R,
+        R: Try<Output = A>,
+    {
+        self.as_mut_slice().try_fold(init, f)
+    }
+
+    /// Folds every element into an accumulator by applying an operation,
+    /// returning the final result
+    ///
+    /// If `f` panics, the element it was called with will be set to `None`
+    pub fn fold<A, F: FnMut(A, &mut Option<T>) -> A>(&mut self, init: A, f: F) -> A {
+        self.as_mut_slice().fold(init, f)
+    }
+
+    /// Calls `f` on every element, stopping early if `f` short-circuits
+    ///
+    /// If `f` panics, the element it was called with will be set to `None`
+    pub fn try_for_each<R, F>(&mut self, f: F) -> R
+    where
+        F: FnMut(&mut Option<T>) -> R,
+        R: Try<Output = ()>,
+    {
+        self.as_mut_slice().try_for_each(f)
+    }
+
+    /// Calls `f` on every element
+    ///
+    /// If `f` panics, the element it was called with will be set to `None`
+    pub fn for_each<F: FnMut(&mut Option<T>)>(&mut self, f: F) {
+        self.as_mut_slice().for_each(f)
+    }
+
+    /// Removes the elements in `range` from the vector, and returns them in an iterator
+    ///
+    /// The elements after the range are moved back when the iterator is dropped,
+    /// if the iterator is leaked, then the vector will be left empty
+    ///
+    /// Panics if the range is decreasing or out of bounds
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T> {
+        let len = self.len();
+
+        let Range { start, end } = to_range(range, len);
+
+        // If the `Drain` is leaked, then all of the elements will be leaked
+        // but the vector will remain valid, because it is left empty
+        let flag = std::mem::take(&mut self.flag);
+        unsafe {
+            self.data.set_len(0);
+        }
+
+        Drain {
+            vec: self,
+            flag,
+            start,
+            index: start,
+            end,
+            tail_start: end,
+            tail_len: len - end,
+        }
+    }
+
+    /// Retains only the elements for which `f` returns true, and removes the rest,
+    /// preserving the order of the retained elements
+    ///
+    /// If `f` panics, the element it was called with will be set to `None`
+    pub fn retain<F: FnMut(Option<&T>) -> bool>(&mut self, mut f: F) {
+        self.retain_mut(|value| f(value.as_ref()))
+    }
+
+    /// Retains only the elements for which `f` returns true, and removes the rest,
+    /// preserving the order of the retained elements
+    ///
+    /// If `f` panics, the element it was called with will be set to `None`
+    pub fn retain_mut<F: FnMut(&mut Option<T>) -> bool>(&mut self, mut f: F) {
+        /// Moves the unprocessed elements back and fixes the length, even if `f` panics
+        struct Guard<'a, T> {
+            vec: &'a mut VecOption<T>,
+            processed: usize,
+            deleted: usize,
+            len: usize,
+        }
+
+        impl<T> Drop for Guard<'_, T> {
+            fn drop(&mut self) {
+                let tail_len = self.len - self.processed;
+
+                unsafe {
+                    if self.deleted != 0 && tail_len != 0 {
+                        let ptr = self.vec.data.as_mut_ptr();
+                        let dest = self.processed - self.deleted;
+
+                        std::ptr::copy(ptr.add(self.processed), ptr.add(dest), tail_len);
+                        self.vec
+                            .flag
+                            .copy_within_unchecked(self.processed, dest, tail_len);
+                    }
+
+                    self.vec.data.set_len(self.len - self.deleted);
+
+                    // clear the flags of the elements that were moved or removed
+                    self.vec.flag.set_len(self.len);
+                    self.vec.flag.truncate(self.len - self.deleted);
+                }
+            }
+        }
+
+        let len = self.len();
+
+        // If the guard is leaked, then all elements will be leaked
+        // but the vector will remain valid
+        unsafe {
+            self.data.set_len(0);
+            self.flag.set_len(0);
+        }
+
+        let mut guard = Guard {
+            vec: self,
+            processed: 0,
+            deleted: 0,
+            len,
+        };
+
+        while guard.processed < len {
+            let index = guard.processed;
+
+            unsafe {
+                let vec = &mut *guard.vec;
+                let data = vec.data.as_mut_ptr().add(index);
+
+                // take the value out before calling `f`, so that if `f` panics
+                // the value will be dropped and the element will be left as `None`
+                let flag = vec.flag.read_unchecked(index);
+                vec.flag.write_unchecked(index, false);
+                let mut value = from_raw_parts(flag, data.read());
+
+                let retain = f(&mut value);
+
+                guard.processed += 1;
+
+                if retain {
+                    let dest = index - guard.deleted;
+
+                    if let Some(value) = value {
+                        guard
+                            .vec
+                            .data
+                            .as_mut_ptr()
+                            .add(dest)
+                            .write(MaybeUninit::new(value));
+                        guard.vec.flag.write_unchecked(dest, true);
+                    } else {
+                        guard.vec.flag.write_unchecked(dest, false);
+                    }
+                } else {
+                    guard.deleted += 1;
+                    drop(value);
+                }
+            }
+        }
+    }
+
+    /// Returns an iterator that takes every `Some` value that matches `pred` out of the vector,
+    /// leaving `None` in its place, and yields it along with its index
+    ///
+    /// Only the elements that are `Some` are visited, and the elements that are
+    /// not visited because the iterator was dropped early are left in place
+    pub fn take_if<F: FnMut(&T) -> bool>(&mut self, pred: F) -> slice::TakeIf<'_, T, F> {
+        slice::TakeIf::new(self.as_mut_slice(), pred)
+    }
+
+    /// Drops every `Some` value that matches `pred`, leaving `None` in its place
+    ///
+    /// Only the elements that are `Some` are visited
+    pub fn clear_if<F: FnMut(&T) -> bool>(&mut self, pred: F) {
+        self.take_if(pred).for_each(drop)
+    }
+
+    /// Clones the elements in `range` and appends them to the end of the vector
+    ///
+    /// Panics if the range is decreasing or out of bounds
+    pub fn extend_from_within<R: RangeBounds<usize>>(&mut self, range: R)
+    where
+        T: Clone,
+    {
+        /// Drops the elements that were already cloned if `clone` panics
+        struct Guard<'a, T> {
+            vec: &'a mut VecOption<T>,
+            start: usize,
+            cloned: usize,
+        }
+
+        impl<T> Drop for Guard<'_, T> {
+            fn drop(&mut self) {
+                let Guard { start, cloned, .. } = *self;
+                let vec = &mut *self.vec;
+                let len = vec.len();
+                let flag = vec.flag.as_slice();
+                let mut index = start;
+
+                while let Some(i) = flag.find(index, true).filter(|&i| i < start + cloned) {
+                    index = i + 1;
+
+                    // the clone of the element at `i` was written to `len + i - start`
+                    unsafe {
+                        vec.data
+                            .as_mut_ptr()
+                            .add(len + i - start)
+                            .cast::<T>()
+                            .drop_in_place();
+                    }
+                }
+            }
+        }
+
+        let len = self.len();
+        let Range { start, end } = to_range(range, len);
+
+        self.data.reserve(end - start);
+        self.flag.reserve(end - start);
+
+        let mut guard = Guard {
+            vec: self,
+            start,
+            cloned: 0,
+        };
+
+        let mut index = start;
+
+        while let Some(i) = guard
+            .vec
+            .flag
+            .as_slice()
+            .find(index, true)
+            .filter(|&i| i < end)
+        {
+            index = i + 1;
+
+            unsafe {
+                // flag is set, so the data is initialized, and there is enough space
+                // to write the clone because of the reserve above
+                let ptr = guard.vec.data.as_mut_ptr();
+                let value = (*ptr.add(i).cast::<T>()).clone();
+                ptr.add(len + i - start).write(MaybeUninit::new(value));
+            }
+
+            guard.cloned = i + 1 - start;
+        }
+
+        std::mem::forget(guard);
+
+        self.flag.extend_from_within(start..end);
+
+        // all of the `Some` elements were cloned into the spare capacity,
+        // and the `None` elements don't need to be initialized
+        unsafe {
+            self.data.set_len(len + end - start);
+        }
+    }
+
+    /// Converts into a `Vec<T>` without reallocating if every element is `Some`,
+    /// otherwise returns the original vector
+    pub fn try_into_vec(self) -> Result<Vec<T>, Self> {
+        if !self.is_all_some() {
+            return Err(self);
+        }
+
+        let mut data = ManuallyDrop::new(self.into_parts().0);
+
+        // every element is initialized
+        unsafe {
+            Ok(Vec::from_raw_parts(
+                data.as_mut_ptr() as *mut T,
+                data.len(),
+                data.capacity(),
+            ))
+        }
+    }
+
+    /// Converts into a `Vec<Option<T>>`, this reuses the allocation if
+    /// `Option<T>` has the same size as `T`
+    pub fn into_vec_of_options(self) -> Vec<Option<T>> {
+        use std::mem::{align_of, size_of};
+
+        if size_of::<Option<T>>() != size_of::<T>() || align_of::<Option<T>>() != align_of::<T>() {
+            return self.into_iter().collect();
+        }
+
+        let (data, flag) = self.into_parts();
+        let mut data = ManuallyDrop::new(data);
+        let ptr = data.as_mut_ptr();
+
+        // `Option<T>` has the same size and alignment as `T`, so each element can be
+        // rewritten in place, and each element is only read once before it is overwritten
+        unsafe {
+            for i in 0..data.len() {
+                let value = from_raw_parts(flag.read_unchecked(i), ptr.add(i).read());
+
+                (ptr.add(i) as *mut Option<T>).write(value);
+            }
+
+            Vec::from_raw_parts(ptr as *mut Option<T>, data.len(), data.capacity())
+        }
+    }
+
+    /// Splits the vector into its data and its packed flags, the flag of the element
+    /// at index `i` is bit `i % usize::BITS` of block `i / usize::BITS`, and the
+    /// element is initialized if its flag is set. The bits past the length are zeroed
+    ///
+    /// Neither the elements nor the flags are copied, but like `Vec::into_boxed_slice`,
+    /// each buffer is reallocated to shrink it if it has spare capacity
+    pub fn into_boxed_parts(self) -> (Box<[MaybeUninit<T>]>, Box<[usize]>) {
+        let (data, flag) = self.into_parts();
+
+        (data.into_boxed_slice(), flag.into_boxed_blocks())
+    }
+
+    /// returns an iterator over references to the elements in the vector
     pub fn iter(&self) -> slice::Iter<'_, T> {
         self.as_slice().iter()
     }
@@ -498,18 +1336,178 @@ impl<T> VecOption<T> {
     pub fn iter_mut(&mut self) -> slice::IterMut<'_, T> {
         self.as_mut_slice().iter_mut()
     }
+
+    /// Counts the number of `Some` elements in the vector
+    pub fn count_some(&self) -> usize {
+        self.as_slice().count_some()
+    }
+
+    /// Counts the number of `None` elements in the vector
+    pub fn count_none(&self) -> usize {
+        self.as_slice().count_none()
+    }
+
+    /// Returns true if every element of the vector is `Some`
+    pub fn is_all_some(&self) -> bool {
+        self.as_slice().is_all_some()
+    }
+
+    /// Returns true if every element of the vector is `None`
+    pub fn is_all_none(&self) -> bool {
+        self.as_slice().is_all_none()
+    }
+
+    /// Finds the index of the first `Some` element
+    pub fn first_some(&self) -> Option<usize> {
+        self.as_slice().first_some()
+    }
+
+    /// Finds the index of the first `None` element, this is useful for finding an empty slot
+    pub fn first_none(&self) -> Option<usize> {
+        self.as_slice().first_none()
+    }
+
+    /// Finds the index of the last `Some` element
+    pub fn last_some(&self) -> Option<usize> {
+        self.as_slice().last_some()
+    }
+
+    /// Finds the index of the last `None` element
+    pub fn last_none(&self) -> Option<usize> {
+        self.as_slice().last_none()
+    }
+
+    /// Finds the index of the first `Some` element at or after `from`
+    pub fn next_some(&self, from: usize) -> Option<usize> {
+        self.as_slice().next_some(from)
+    }
+
+    /// Finds the index of the first `None` element at or after `from`
+    pub fn next_none(&self, from: usize) -> Option<usize> {
+        self.as_slice().next_none(from)
+    }
+
+    /// Finds the index of the last `Some` element before `from`
+    pub fn prev_some(&self, from: usize) -> Option<usize> {
+        self.as_slice().prev_some(from)
+    }
+
+    /// Finds the index of the last `None` element before `from`
+    pub fn prev_none(&self, from: usize) -> Option<usize> {
+        self.as_slice().prev_none(from)
+    }
+
+    /// Finds the first index that starts a run of at least `k` `None` elements,
+    /// this is useful for finding space for `k` contiguous elements
+    pub fn find_none_run(&self, k: usize) -> Option<usize> {
+        self.as_slice().find_none_run(k)
+    }
+
+    /// Returns an iterator over the ranges of each maximal run of `None` elements
+    pub fn none_runs(&self) -> slice::Runs<'_> {
+        self.as_slice().none_runs()
+    }
+
+    /// Returns an iterator over the ranges of each maximal run of `Some` elements
+    pub fn some_runs(&self) -> slice::Runs<'_> {
+        self.as_slice().some_runs()
+    }
+
+    /// Returns an iterator over each maximal run of `Some` elements as a slice,
+    /// along with the index of the start of the run
+    pub fn some_slices(&self) -> slice::SomeSlices<'_, T> {
+        self.as_slice().some_slices()
+    }
+
+    /// Returns an iterator over each maximal run of `Some` elements as a mutable slice,
+    /// along with the index of the start of the run
+    pub fn some_slices_mut(&mut self) -> slice::SomeSlicesMut<'_, T> {
+        self.as_mut_slice().some_slices_mut()
+    }
+
+    /// Returns the elements as a slice if every element is `Some`
+    pub fn as_some_slice(&self) -> Option<&[T]> {
+        self.as_slice().as_some_slice()
+    }
+
+    /// Returns the elements as a mutable slice if every element is `Some`
+    pub fn as_some_slice_mut(&mut self) -> Option<&mut [T]> {
+        self.as_mut_slice().as_some_slice_mut()
+    }
+
+    /// Counts the number of `Some` elements before `index`, this scans the flags
+    /// one block at a time, use `rank_select` when making many queries
+    ///
+    /// Panics if `index > len`
+    pub fn rank_some(&self, index: usize) -> usize {
+        self.as_slice().rank_some(index)
+    }
+
+    /// Finds the index of the `k`-th `Some` element (counting from zero), this scans
+    /// the flags one block at a time, use `rank_select` when making many queries
+    pub fn select_some(&self, k: usize) -> Option<usize> {
+        self.as_slice().select_some(k)
+    }
+
+    /// Builds an index for fast `rank_some` and `select_some` queries,
+    /// the vector can't be modified while the index is alive
+    pub fn rank_select(&self) -> slice::RankSelect<'_> {
+        self.as_slice().rank_select()
+    }
+
+    /// returns an iterator over references to the `Some` elements in the vector
+    /// and their indices
+    pub fn iter_some(&self) -> slice::IterSome<'_, T> {
+        self.as_slice().iter_some()
+    }
+
+    /// returns an iterator over lazy proxies to the elements in the vector,
+    /// the values are only moved if an option is reassigned
+    pub fn iter_lazy_mut(&mut self) -> slice::IterLazyMut<'_, T> {
+        self.as_mut_slice().iter_lazy_mut()
+    }
+
+    /// returns an iterator over mutable references to the `Some` elements in
+    /// the vector and their indices
+    pub fn iter_some_mut(&mut self) -> slice::IterSomeMut<'_, T> {
+        self.as_mut_slice().iter_some_mut()
+    }
+
+    /// returns an iterator over the `Some` elements in the vector and their indices,
+    /// the `None` elements are skipped without being visited
+    pub fn into_iter_some(self) -> IntoIterSome<T> {
+        let (data, flag) = self.into_parts();
+
+        IntoIterSome {
+            start: 0,
+            end: flag.len(),
+            count: flag.as_slice().count_ones(),
+            data,
+            flag,
+        }
+    }
 }
 
 impl<T> Drop for VecOption<T> {
     fn drop(&mut self) {
         if std::mem::needs_drop::<T>() {
-            self.clear()
+            self.as_mut_slice().set_none()
         }
     }
 }
 
 fn clone_impl<T: Clone>(vec: &VecOption<T>) -> VecOption<T> {
-    vec.iter().map(|x| x.cloned()).collect()
+    vec.as_slice().to_vec_option()
+}
+
+fn clone_from_impl<T: Clone>(vec: &mut VecOption<T>, source: &VecOption<T>) {
+    vec.truncate(source.len());
+
+    let len = vec.len();
+    let (init, tail) = source.as_slice().split_at(len).unwrap();
+
+    vec.as_mut_slice().clone_from_slice(init);
+    vec.extend(tail.iter().map(|x| x.cloned()));
 }
 
 impl<T: Clone> Clone for VecOption<T> {
@@ -522,23 +1520,53 @@ impl<T: Clone> Clone for VecOption<T> {
     fn clone(&self) -> Self {
         clone_impl(self)
     }
+
+    #[cfg(feature = "nightly")]
+    default fn clone_from(&mut self, source: &Self) {
+        clone_from_impl(self, source)
+    }
+
+    #[cfg(not(feature = "nightly"))]
+    fn clone_from(&mut self, source: &Self) {
+        clone_from_impl(self, source)
+    }
 }
 
 #[cfg(feature = "nightly")]
 impl<T: Copy> Clone for VecOption<T> {
     fn clone(&self) -> Self {
-        let len = self.len();
-        let mut new = Self {
-            data: Vec::with_capacity(len),
-            flag: self.flag.clone(),
-        };
+        self.clone_copy()
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.clone_from_copy(source)
+    }
+}
+
+impl<T: Copy> VecOption<T> {
+    /// Clones the vector by copying the data with a `memcpy`, and cloning the flags
+    pub fn clone_copy(&self) -> Self {
+        let mut new = Self::new();
+        new.clone_from_copy(self);
+        new
+    }
 
+    /// Replaces the contents of the vector with a copy of `source`,
+    /// reusing the existing buffers
+    pub fn clone_from_copy(&mut self, source: &Self) {
+        let len = source.len();
+
+        self.data.clear();
+        self.data.reserve(len);
+
+        // `Copy` types don't need to be dropped, and there is enough space
+        // because of the `reserve` above
         unsafe {
-            new.data.set_len(len);
-            new.data.copy_from_slice(&self.data);
+            std::ptr::copy_nonoverlapping(source.data.as_ptr(), self.data.as_mut_ptr(), len);
+            self.data.set_len(len);
         }
 
-        new
+        self.flag.clone_from(&source.flag);
     }
 }
 
@@ -584,25 +1612,13 @@ impl<T: Hash> Hash for VecOption<T> {
 
 impl<T> std::iter::Extend<Option<T>> for VecOption<T> {
     fn extend<I: IntoIterator<Item = Option<T>>>(&mut self, iter: I) {
-        let iter = iter.into_iter();
-
-        let (additional, _) = iter.size_hint();
-
-        self.reserve(additional);
-
-        iter.for_each(|x| self.push(x));
+        self.extend_iter(iter.into_iter());
     }
 }
 
 impl<T> std::iter::Extend<T> for VecOption<T> {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        let iter = iter.into_iter();
-
-        let (additional, _) = iter.size_hint();
-
-        self.reserve(additional);
-
-        iter.for_each(|x| self.push(x));
+        self.extend_iter(iter.into_iter().map(Some));
     }
 }
 
@@ -631,13 +1647,97 @@ impl<T> From<Vec<T>> for VecOption<T> {
     }
 }
 
+impl<T> From<VecOption<T>> for Vec<Option<T>> {
+    fn from(vec: VecOption<T>) -> Self {
+        vec.into_vec_of_options()
+    }
+}
+
+impl<T> TryFrom<VecOption<T>> for Vec<T> {
+    type Error = VecOption<T>;
+
+    fn try_from(vec: VecOption<T>) -> Result<Self, Self::Error> {
+        vec.try_into_vec()
+    }
+}
+
+impl<T> std::iter::FromIterator<T> for VecOption<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<T>>())
+    }
+}
+
 impl<T> From<Vec<Option<T>>> for VecOption<T> {
+    /// Reuses the allocation if it has a compatible layout
     fn from(vec: Vec<Option<T>>) -> Self {
-        let mut vec_opt = VecOption::new();
+        use std::mem::{align_of, size_of};
+
+        let size = size_of::<T>();
+        let bytes = vec.capacity() * size_of::<Option<T>>();
+
+        // the allocation can only be reused if it has the same size and alignment
+        // as an allocation of `MaybeUninit<T>`
+        if size == 0 || bytes % size != 0 || align_of::<Option<T>>() != align_of::<T>() {
+            let mut vec_opt = VecOption::new();
+
+            vec_opt.extend(vec);
+
+            return vec_opt;
+        }
+
+        let len = vec.len();
+        let mut flag = BitVec::with_capacity(len);
+        let mut vec = ManuallyDrop::new(vec);
+        let src = vec.as_mut_ptr();
+        let dest = src as *mut MaybeUninit<T>;
+
+        // each `T` is written at or before the position of the `Option<T>` it was read
+        // from, and ends before the next `Option<T>`, so no element is overwritten
+        // before it is read
+        unsafe {
+            for start in (0..len).step_by(bit_vec::BITS) {
+                let n = (len - start).min(bit_vec::BITS);
+                let mut block: bit_vec::Block = 0;
+
+                for i in start..start + n {
+                    if let Some(value) = src.add(i).read() {
+                        dest.add(i).write(MaybeUninit::new(value));
+                        block |= 1 << (i - start);
+                    }
+                }
+
+                flag.push_block(block, n as u8);
+            }
+
+            Self {
+                data: Vec::from_raw_parts(dest, len, bytes / size),
+                flag,
+            }
+        }
+    }
+}
+
+impl<T> From<Box<[Option<T>]>> for VecOption<T> {
+    fn from(slice: Box<[Option<T>]>) -> Self {
+        Self::from(slice.into_vec())
+    }
+}
 
-        vec_opt.extend(vec);
+impl<T: Clone> From<&[Option<T>]> for VecOption<T> {
+    fn from(slice: &[Option<T>]) -> Self {
+        slice.iter().cloned().collect()
+    }
+}
+
+impl<T, const N: usize> From<[Option<T>; N]> for VecOption<T> {
+    fn from(array: [Option<T>; N]) -> Self {
+        IntoIterator::into_iter(array).collect()
+    }
+}
 
-        vec_opt
+impl<T, const N: usize> From<[T; N]> for VecOption<T> {
+    fn from(array: [T; N]) -> Self {
+        Self::from(Vec::from(array))
     }
 }
 
@@ -671,17 +1771,19 @@ impl<T> Iterator for IntoIter<T> {
 
     fn nth(&mut self, n: usize) -> Option<Self::Item> {
         if std::mem::needs_drop::<T>() {
-            for _ in 1..n {
+            // drop each skipped element through `next`, so that every
+            // `Some` value is dropped exactly once
+            for _ in 0..n {
                 self.next()?;
             }
             self.next()
         } else {
-            unsafe {
-                let flag = self.flag.nth(n)?;
-                let data = self.data.nth(n).unwrap_unchecked();
+            // both iterators have the same length, so they will be
+            // advanced by the same amount, even if `n` is out of bounds
+            let flag = self.flag.nth(n);
+            let data = self.data.nth(n);
 
-                Some(from_raw_parts(flag, data))
-            }
+            unsafe { Some(from_raw_parts(flag?, data.unwrap_unchecked())) }
         }
     }
 }
@@ -699,17 +1801,15 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
     #[cfg(feature = "nightly")]
     fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
         if std::mem::needs_drop::<T>() {
-            for _ in 1..n {
+            for _ in 0..n {
                 self.next_back()?;
             }
             self.next_back()
         } else {
-            unsafe {
-                let flag = self.flag.nth_back(n)?;
-                let data = self.data.nth_back(n).unwrap_unchecked();
+            let flag = self.flag.nth_back(n);
+            let data = self.data.nth_back(n);
 
-                Some(from_raw_parts(flag, data))
-            }
+            unsafe { Some(from_raw_parts(flag?, data.unwrap_unchecked())) }
         }
     }
 }
@@ -717,27 +1817,224 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
 impl<T> ExactSizeIterator for IntoIter<T> {}
 impl<T> std::iter::FusedIterator for IntoIter<T> {}
 
-impl<'a, T> IntoIterator for &'a mut VecOption<T> {
-    type Item = OptionProxy<'a, T>;
-    type IntoIter = slice::IterMut<'a, T>;
+impl<T: Clone> Clone for IntoIter<T> {
+    fn clone(&self) -> Self {
+        self.as_slice()
+            .iter()
+            .map(|x| x.cloned())
+            .collect::<VecOption<T>>()
+            .into_iter()
+    }
+}
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.iter_mut()
+impl<T: fmt::Debug> fmt::Debug for IntoIter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
     }
 }
 
-impl<'a, T> IntoIterator for &'a VecOption<T> {
-    type Item = Option<&'a T>;
-    type IntoIter = slice::Iter<'a, T>;
+impl<T> IntoIterator for VecOption<T> {
+    type Item = Option<T>;
+    type IntoIter = IntoIter<T>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.iter()
+        let (data, flag) = self.into_parts();
+
+        IntoIter {
+            data: data.into_iter(),
+            flag: flag.into_iter(),
+        }
     }
 }
 
-use std::fmt;
-
-impl<T: fmt::Debug> fmt::Debug for VecOption<T> {
+/// This struct is created by the `into_iter_some` method on `VecOption`
+pub struct IntoIterSome<T> {
+    data: Vec<MaybeUninit<T>>,
+    flag: BitVec,
+    start: usize,
+    end: usize,
+    count: usize,
+}
+
+impl<T> Drop for IntoIterSome<T> {
+    fn drop(&mut self) {
+        if std::mem::needs_drop::<T>() {
+            self.for_each(drop);
+        }
+    }
+}
+
+impl<T> Iterator for IntoIterSome<T> {
+    type Item = (usize, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.count = self.count.checked_sub(1)?;
+
+        unsafe {
+            // there is at least one set flag in `start..end`
+            let index = self
+                .flag
+                .as_slice()
+                .find(self.start, true)
+                .unwrap_unchecked();
+            self.start = index + 1;
+
+            // the flag is set, and the element will not be read again
+            Some((index, self.data.as_ptr().add(index).read().assume_init()))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.count, Some(self.count))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIterSome<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.count = self.count.checked_sub(1)?;
+
+        unsafe {
+            // there is at least one set flag in `start..end`
+            let index = self
+                .flag
+                .as_slice()
+                .rfind(self.end, true)
+                .unwrap_unchecked();
+            self.end = index;
+
+            // the flag is set, and the element will not be read again
+            Some((index, self.data.as_ptr().add(index).read().assume_init()))
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIterSome<T> {}
+impl<T> std::iter::FusedIterator for IntoIterSome<T> {}
+
+/// This struct is created by the `drain` method on `VecOption`
+pub struct Drain<'a, T> {
+    vec: &'a mut VecOption<T>,
+    /// the flags of the whole vector, these are moved back when the `Drain` is dropped
+    flag: BitVec,
+    start: usize,
+    index: usize,
+    end: usize,
+    tail_start: usize,
+    tail_len: usize,
+}
+
+impl<T> Drain<'_, T> {
+    /// # Safety
+    ///
+    /// `index` must be in the range that has not been yielded yet,
+    /// and must not be read again
+    unsafe fn read(&self, index: usize) -> Option<T> {
+        // the elements in the drained range are past the length of the vector,
+        // but they are still initialized
+        let flag = self.flag.read_unchecked(index);
+        let data = self.vec.data.as_ptr().add(index).read();
+
+        from_raw_parts(flag, data)
+    }
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = Option<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index < self.end {
+            let index = self.index;
+            self.index += 1;
+
+            unsafe { Some(self.read(index)) }
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.index;
+
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Drain<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.index < self.end {
+            self.end -= 1;
+
+            unsafe { Some(self.read(self.end)) }
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+impl<T> std::iter::FusedIterator for Drain<'_, T> {}
+
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        /// Moves the tail back, even if dropping one of the elements panics
+        struct DropGuard<'r, 'a, T>(&'r mut Drain<'a, T>);
+
+        impl<T> Drop for DropGuard<'_, '_, T> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                let vec = &mut *drain.vec;
+                let start = drain.start;
+                let tail_start = drain.tail_start;
+                let tail_len = drain.tail_len;
+
+                unsafe {
+                    if tail_start != start {
+                        let ptr = vec.data.as_mut_ptr();
+                        std::ptr::copy(ptr.add(tail_start), ptr.add(start), tail_len);
+
+                        // the flags still have the length of the whole vector
+                        drain
+                            .flag
+                            .copy_within_unchecked(tail_start, start, tail_len);
+                    }
+
+                    vec.data.set_len(start + tail_len);
+                }
+
+                drain.flag.truncate(start + tail_len);
+                vec.flag = std::mem::take(&mut drain.flag);
+            }
+        }
+
+        let guard = DropGuard(self);
+
+        if std::mem::needs_drop::<T>() {
+            guard.0.for_each(drop);
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut VecOption<T> {
+    type Item = OptionProxy<'a, T>;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a VecOption<T> {
+    type Item = Option<&'a T>;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+use std::fmt;
+
+impl<T: fmt::Debug> fmt::Debug for VecOption<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self).finish()
     }
@@ -890,3 +2187,1208 @@ fn test() {
         ]
     );
 }
+
+#[test]
+fn into_iter() {
+    use std::rc::Rc;
+
+    let vec: VecOption<_> = vec![Some(0), None, Some(2), Some(3), None, Some(5)]
+        .into_iter()
+        .collect();
+
+    assert!(vec
+        .clone()
+        .into_iter()
+        .eq(vec![Some(0), None, Some(2), Some(3), None, Some(5)]));
+
+    assert!(vec
+        .clone()
+        .into_iter()
+        .rev()
+        .eq(vec![Some(5), None, Some(3), Some(2), None, Some(0)]));
+
+    let mut iter = vec.into_iter();
+
+    assert_eq!(iter.nth(2), Some(Some(2)));
+    assert!(iter.as_slice().iter().eq(vec![Some(&3), None, Some(&5)]));
+    assert_eq!(iter.len(), 3);
+
+    iter.as_mut_slice().replace(1, 4);
+
+    assert_eq!(
+        iter.clone().collect::<Vec<_>>(),
+        [Some(3), Some(4), Some(5)]
+    );
+    assert_eq!(
+        format!("{:?}", iter),
+        "IntoIter([Some(3), Some(4), Some(5)])"
+    );
+    assert_eq!(iter.nth(5), None);
+    assert_eq!(iter.next(), None);
+
+    let rc = Rc::new(());
+    let vec: VecOption<Rc<()>> = (0..10)
+        .map(|i| if i % 3 == 0 { None } else { Some(rc.clone()) })
+        .collect();
+
+    assert_eq!(Rc::strong_count(&rc), 7);
+
+    let mut iter = vec.into_iter();
+
+    // skips 2 `Some`s and 2 `None`s, and drops the returned `Some`
+    assert!(iter.nth(4).unwrap().is_some());
+    assert_eq!(Rc::strong_count(&rc), 4);
+
+    assert!(iter.nth(20).is_none());
+    assert_eq!(Rc::strong_count(&rc), 1);
+
+    drop(iter);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn with_mut() {
+    use std::ops::ControlFlow;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::rc::Rc;
+
+    let mut vec = VecOption::from(vec![0, 1, 2, 3, 4]);
+
+    assert_eq!(vec.with_mut(2, |x| x.replace(20)), Some(Some(2)));
+    assert_eq!(vec.with_mut(3, Option::take), Some(Some(3)));
+    assert_eq!(vec.with_mut(5, Option::take), None);
+    assert_eq!(vec, [Some(0), Some(1), Some(20), None, Some(4)]);
+
+    let res = vec.try_for_each(|x| match x {
+        Some(20) => ControlFlow::Break(()),
+        _ => {
+            *x = x.map(|x| x + 1);
+            ControlFlow::Continue(())
+        }
+    });
+
+    assert_eq!(res, ControlFlow::Break(()));
+    assert_eq!(vec, [Some(1), Some(2), Some(20), None, Some(4)]);
+
+    let count = vec
+        .get_mut(1..)
+        .unwrap()
+        .fold(0, |count, x| count + x.is_some() as usize);
+    assert_eq!(count, 3);
+
+    let rc = Rc::new(());
+    let mut vec: VecOption<_> = (0..4).map(|_| Some(rc.clone())).collect();
+
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        vec.for_each(|x| {
+            if Rc::strong_count(x.as_ref().unwrap()) == 3 {
+                panic!()
+            }
+
+            *x = None;
+        })
+    }));
+
+    // the first two were set to `None`, the third was dropped by the panic
+    assert!(res.is_err());
+    assert_eq!(Rc::strong_count(&rc), 2);
+    assert!(vec
+        .iter()
+        .map(|x| x.is_some())
+        .eq(vec![false, false, false, true]));
+}
+
+#[test]
+fn insert_remove() {
+    let mut vec = VecOption::new();
+
+    vec.insert(0, 1);
+    vec.insert(0, None);
+    vec.insert(2, 3);
+    vec.insert(1, Some(2));
+
+    assert_eq!(vec, [None, Some(2), Some(1), Some(3)]);
+
+    vec.extend(4..20);
+
+    assert_eq!(vec.remove(1), Some(2));
+    assert_eq!(vec.remove(0), None);
+    assert_eq!(vec.swap_remove(0), Some(1));
+    assert_eq!(vec.swap_remove(16), Some(18));
+
+    assert_eq!(
+        vec,
+        [19, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17][..]
+    );
+
+    assert!(std::panic::catch_unwind(move || vec.remove(16)).is_err());
+}
+
+#[test]
+fn drain() {
+    use std::rc::Rc;
+
+    let mut vec: VecOption<i32> = (0..20)
+        .map(|i| if i % 3 == 0 { None } else { Some(i) })
+        .collect();
+    let mut model: Vec<_> = vec.iter().map(|x| x.cloned()).collect();
+
+    assert!(vec.drain(2..9).eq(model.drain(2..9)));
+    assert_eq!(vec, model);
+
+    assert!(vec.drain(..=3).rev().eq(model.drain(..=3).rev()));
+    assert_eq!(vec, model);
+
+    assert!(vec.drain(4..4).eq(model.drain(4..4)));
+    assert!(vec.drain(5..).eq(model.drain(5..)));
+    assert_eq!(vec, model);
+
+    assert!(vec.drain(..).eq(model.drain(..)));
+    assert!(vec.is_empty());
+
+    let rc = Rc::new(());
+    let mut vec: VecOption<_> = (0..20).map(|_| Some(rc.clone())).collect();
+
+    let mut drain = vec.drain(3..12);
+    drain.next();
+    drain.next_back();
+    drop(drain);
+
+    assert_eq!(vec.len(), 11);
+    assert_eq!(Rc::strong_count(&rc), 12);
+
+    std::mem::forget(vec.drain(5..7));
+
+    // the vector is left empty, with no flags set past the length
+    assert!(vec.is_empty());
+    assert_eq!(vec.flag.len(), 0);
+
+    vec.push(rc.clone());
+    vec.clear();
+
+    // the leaked elements are never dropped
+    assert_eq!(Rc::strong_count(&rc), 12);
+}
+
+#[test]
+fn retain() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::rc::Rc;
+
+    let mut vec: VecOption<_> = (0..20)
+        .map(|i| if i % 3 == 0 { None } else { Some(i) })
+        .collect();
+
+    vec.retain(|x| !matches!(x, Some(x) if x % 2 != 0));
+
+    assert_eq!(
+        vec,
+        [
+            None,
+            Some(2),
+            None,
+            Some(4),
+            None,
+            Some(8),
+            None,
+            Some(10),
+            None,
+            Some(14),
+            None,
+            Some(16),
+            None
+        ]
+    );
+
+    vec.retain_mut(|x| match x {
+        Some(value) if *value > 4 => {
+            *value /= 2;
+            true
+        }
+        Some(_) => false,
+        None => {
+            *x = Some(0);
+            true
+        }
+    });
+
+    assert_eq!(vec, [0, 0, 0, 4, 0, 5, 0, 7, 0, 8, 0][..]);
+
+    let rc = Rc::new(());
+    let mut vec: VecOption<Rc<()>> = (0..10).map(|_| Some(rc.clone())).collect();
+    let mut index = 0;
+
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        vec.retain(|_| {
+            index += 1;
+
+            if index == 6 {
+                panic!()
+            }
+
+            index % 2 == 0
+        })
+    }));
+
+    // 3 elements were removed, and the sixth was dropped by the panic
+    assert!(res.is_err());
+    assert_eq!(vec.len(), 7);
+    assert_eq!(Rc::strong_count(&rc), 7);
+    assert!(vec
+        .iter()
+        .map(|x| x.is_some())
+        .eq(vec![true, true, false, true, true, true, true]));
+}
+
+#[test]
+fn take_if() {
+    let mut vec: VecOption<_> = (0..40)
+        .map(|i| if i % 3 == 0 { None } else { Some(i) })
+        .collect();
+
+    let taken: Vec<_> = vec.take_if(|x| x % 5 == 0).collect();
+
+    assert_eq!(taken, [(5, 5), (10, 10), (20, 20), (25, 25), (35, 35)]);
+    assert_eq!(vec.len(), 40);
+    assert!(vec.iter().enumerate().all(|(i, x)| match x {
+        Some(&x) => x == i && i % 3 != 0 && i % 5 != 0,
+        None => i % 3 == 0 || i % 5 == 0,
+    }));
+
+    vec.get_mut(10..20).unwrap().clear_if(|x| x % 2 == 0);
+
+    assert_eq!(
+        vec.get(8..22).unwrap().iter().collect::<Vec<_>>(),
+        [
+            Some(&8),
+            None,
+            None,
+            Some(&11),
+            None,
+            Some(&13),
+            None,
+            None,
+            None,
+            Some(&17),
+            None,
+            Some(&19),
+            None,
+            None
+        ]
+    );
+
+    assert_eq!(vec.take_if(|_| true).next(), Some((1, 1)));
+
+    assert_eq!(vec.get(2), Some(Some(&2)));
+}
+
+#[test]
+fn split_off() {
+    use std::rc::Rc;
+
+    let mut vec: VecOption<i32> = (0..20)
+        .map(|i| if i % 3 == 0 { None } else { Some(i) })
+        .collect();
+    let model: Vec<_> = vec.iter().map(|x| x.cloned()).collect();
+
+    let mut tail = vec.split_off(13);
+
+    assert_eq!(vec, &model[..13]);
+    assert_eq!(tail, &model[13..]);
+
+    vec.extend_from_within(2..=6);
+    vec.extend_from_within(..);
+
+    let mut expected = model[..13].to_vec();
+    expected.extend_from_within(2..=6);
+    expected.extend_from_within(..);
+
+    assert_eq!(vec, expected);
+
+    vec.append(&mut tail);
+    expected.extend_from_slice(&model[13..]);
+
+    assert_eq!(vec, expected);
+    assert!(tail.is_empty());
+
+    struct PanicOnClone(Rc<()>);
+
+    impl Clone for PanicOnClone {
+        fn clone(&self) -> Self {
+            if Rc::strong_count(&self.0) == 5 {
+                panic!()
+            }
+
+            PanicOnClone(self.0.clone())
+        }
+    }
+
+    let rc = Rc::new(());
+    let mut vec: VecOption<PanicOnClone> = (0..6)
+        .map(|i| {
+            if i % 2 == 0 {
+                None
+            } else {
+                Some(PanicOnClone(rc.clone()))
+            }
+        })
+        .collect();
+
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        vec.extend_from_within(..);
+    }));
+
+    assert!(res.is_err());
+    assert_eq!(vec.len(), 6);
+    assert_eq!(Rc::strong_count(&rc), 4);
+}
+
+#[test]
+fn resize() {
+    let mut vec = VecOption::from_fn(10, |i| if i % 3 == 0 { None } else { Some(i) });
+
+    assert_eq!(
+        vec,
+        [
+            None,
+            Some(1),
+            Some(2),
+            None,
+            Some(4),
+            Some(5),
+            None,
+            Some(7),
+            Some(8),
+            None
+        ]
+    );
+
+    vec.resize(4, 10);
+    assert_eq!(vec, [None, Some(1), Some(2), None]);
+
+    vec.resize(7, 10);
+    assert_eq!(
+        vec,
+        [None, Some(1), Some(2), None, Some(10), Some(10), Some(10)]
+    );
+
+    vec.resize(9, None);
+    assert_eq!(vec.len(), 9);
+    assert!(vec.get(6..).unwrap().iter().eq(vec![Some(&10), None, None]));
+
+    let mut counter = 0;
+    vec.resize_with(30, || {
+        counter += 1;
+        Some(counter).filter(|x| x % 2 == 0)
+    });
+
+    let expected: Vec<_> = (1..=21).map(|x| Some(x).filter(|x| x % 2 == 0)).collect();
+
+    assert_eq!(vec.len(), 30);
+    assert!(vec.iter().skip(9).eq(expected.iter().map(Option::as_ref)));
+
+    vec.resize_with(2, || None);
+    assert_eq!(vec, [None, Some(1)]);
+
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        vec.resize_with(20, || {
+            counter += 1;
+
+            if counter == 35 {
+                panic!()
+            }
+
+            Some(counter)
+        })
+    }));
+
+    // the elements that were created before the panic are kept
+    assert!(res.is_err());
+    assert!(vec
+        .iter()
+        .skip(2)
+        .eq((22..35).collect::<Vec<_>>().iter().map(Some)));
+}
+
+#[test]
+fn from_fn_block_boundaries() {
+    use bit_vec::BITS;
+
+    // nothing is written to the flags if there are no elements
+    let vec = VecOption::<u8>::from_fn(0, |_| None);
+    assert!(vec.is_empty());
+
+    // the writer is flushed after the last full block, and not again on drop
+    let vec = VecOption::from_fn(BITS, |i| Some(i).filter(|i| i % 2 == 0));
+    assert_eq!(vec.len(), BITS);
+    assert_eq!(vec.count_some(), BITS / 2);
+    assert!(vec.iter_some().all(|(i, &x)| i == x));
+
+    // the flags only have room for exactly `BITS` elements
+    let mut vec = VecOption::with_capacity(BITS);
+    vec.resize_with(BITS, || Some(1_u8));
+    assert_eq!(vec.len(), BITS);
+    assert!(vec.is_all_some());
+
+    vec.push(None);
+    assert_eq!(vec.len(), BITS + 1);
+    assert_eq!(vec.count_some(), BITS);
+}
+
+#[test]
+fn with_len_none() {
+    let mut vec = VecOption::<String>::with_len_none(100);
+
+    assert_eq!(vec.len(), 100);
+    assert!(vec.iter().all(|x| x.is_none()));
+
+    vec.replace(63, String::from("hello"));
+    vec.push(String::from("world"));
+
+    assert_eq!(vec.get(63), Some(Some(&String::from("hello"))));
+    assert_eq!(vec.get(100), Some(Some(&String::from("world"))));
+    assert_eq!(vec.iter().filter(|x| x.is_some()).count(), 2);
+
+    assert!(VecOption::<String>::with_len_none(0).is_empty());
+}
+
+#[test]
+fn capacity() {
+    let mut vec = VecOption::<u32>::with_capacity(100);
+
+    let cap = vec.capacity();
+    assert!(cap.data >= 100);
+    assert!(cap.flag >= 100);
+    assert!(cap.flag < 100 + bit_vec::BITS);
+
+    vec.extend(0..100);
+    assert_eq!(vec.capacity(), cap);
+
+    vec.reserve(1);
+    assert!(vec.capacity().data >= 101);
+    assert!(vec.capacity().flag >= 101);
+
+    vec.truncate(10);
+    vec.shrink_to(50);
+    assert!(vec.capacity().data >= 50);
+    assert!(vec.capacity().flag >= 50);
+    assert!(vec.capacity().flag < 50 + bit_vec::BITS);
+
+    vec.shrink_to_fit();
+    assert_eq!(vec.capacity().data, 10);
+    assert_eq!(vec.capacity().flag, bit_vec::BITS);
+    assert!(vec.iter().eq((0..10).collect::<Vec<_>>().iter().map(Some)));
+
+    assert!(vec.try_reserve(usize::MAX).is_err());
+    assert!(vec.try_reserve_exact(usize::MAX / 2).is_err());
+    assert!(vec.try_reserve_exact(20).is_ok());
+    assert!(vec.capacity().data >= 30);
+    assert!(vec.capacity().flag >= 30);
+}
+
+#[test]
+fn iter_some() {
+    let model: Vec<_> = (0..300)
+        .map(|i| {
+            if i % 7 == 0 || (130..200).contains(&i) {
+                Some(i)
+            } else {
+                None
+            }
+        })
+        .collect();
+    let expected: Vec<_> = model
+        .iter()
+        .enumerate()
+        .filter_map(|(i, x)| Some((i, (*x)?)))
+        .collect();
+
+    let mut vec: VecOption<_> = model.iter().cloned().collect();
+
+    assert_eq!(vec.iter_some().len(), expected.len());
+    assert!(vec
+        .iter_some()
+        .map(|(i, x)| (i, *x))
+        .eq(expected.iter().cloned()));
+    assert!(vec
+        .iter_some()
+        .rev()
+        .map(|(i, x)| (i, *x))
+        .eq(expected.iter().rev().cloned()));
+
+    // unaligned slices and meeting in the middle
+    let slice = vec.as_slice().get(3..250).unwrap();
+    let mut iter = slice.iter_some();
+    let mut front = Vec::new();
+    let mut back = Vec::new();
+    while let Some((i, &x)) = iter.next() {
+        front.push((i + 3, x));
+        back.extend(iter.next_back().map(|(i, &x)| (i + 3, x)));
+    }
+    back.reverse();
+    front.extend(back);
+    assert!(front
+        .iter()
+        .eq(expected.iter().filter(|(i, _)| (3..250).contains(i))));
+
+    for (i, x) in vec.iter_some_mut() {
+        *x += i;
+    }
+    assert!(vec
+        .iter_some()
+        .map(|(i, x)| (i, *x))
+        .eq(expected.iter().map(|&(i, x)| (i, x * 2))));
+
+    let strings: VecOption<_> = model.iter().map(|x| x.map(|x| x.to_string())).collect();
+    let mut iter = strings.into_iter_some();
+    assert_eq!(iter.next(), Some((0, String::from("0"))));
+    assert_eq!(iter.next_back(), Some((294, String::from("294"))));
+    assert_eq!(iter.len(), expected.len() - 2);
+    // the remaining elements are dropped with the iterator
+    drop(iter);
+
+    assert_eq!(VecOption::<u8>::with_len_none(200).iter_some().next(), None);
+}
+
+#[test]
+fn get_some_mut() {
+    let mut vec: VecOption<String> =
+        VecOption::from(vec![Some(String::from("a")), None, Some(String::from("c"))]);
+
+    vec.get_some_mut(0).unwrap().push('b');
+    assert_eq!(vec.get_some_mut(1), None);
+    assert_eq!(vec.get_some_mut(3), None);
+
+    let data = vec.get_some_mut(2).unwrap().as_ptr();
+    assert_eq!(vec.as_mut_slice().get_some_mut(2).unwrap().as_ptr(), data);
+
+    let mut proxy = vec.get_lazy_mut(2).unwrap();
+    assert!(proxy.is_some());
+    // the value is not moved out of the vector
+    assert_eq!(proxy.as_ref().unwrap().as_ptr(), data);
+    assert_eq!(proxy.take(), Some(String::from("c")));
+    assert!(proxy.is_none());
+    proxy.insert(String::from("d")).push('e');
+    drop(proxy);
+    assert_eq!(
+        vec,
+        [Some("ab"), None, Some("de")].map(|x| x.map(String::from))
+    );
+
+    let mut proxy = vec.get_lazy_mut(1).unwrap().into_proxy();
+    *proxy = Some(String::from("x"));
+    drop(proxy);
+
+    // leaking the lazy proxy keeps the vector in sync
+    let mut proxy = vec.get_lazy_mut(0).unwrap();
+    proxy.take();
+    std::mem::forget(proxy);
+    assert_eq!(
+        vec,
+        [None, Some("x"), Some("de")].map(|x| x.map(String::from))
+    );
+    assert!(vec.get_lazy_mut(3).is_none());
+}
+
+#[test]
+fn iter_lazy_mut() {
+    type Big = [u8; 2048];
+
+    let mut vec: VecOption<Big> = (0..10)
+        .map(|i| if i % 3 == 0 { None } else { Some([i; 2048]) })
+        .collect();
+    let ptrs: Vec<_> = vec.iter().map(|x| x.map(|x| x as *const Big)).collect();
+
+    // the proxies point straight into the vector
+    assert!(vec
+        .iter_lazy_mut()
+        .map(|x| x.as_ref().map(|x| x as *const Big))
+        .eq(ptrs.iter().cloned()));
+    assert!(vec
+        .iter_lazy_mut()
+        .rev()
+        .map(|x| x.as_ref().map(|x| x as *const Big))
+        .eq(ptrs.iter().rev().cloned()));
+
+    // the values are not moved out of the vector unless the option is reassigned,
+    // so leaking the proxies leaves every element in place
+    vec.iter_lazy_mut().for_each(std::mem::forget);
+    assert!(vec.iter().map(|x| x.map(|x| x[0])).eq((0..10).map(|i| {
+        if i % 3 == 0 {
+            None
+        } else {
+            Some(i)
+        }
+    })));
+
+    for (i, mut x) in vec.iter_lazy_mut().enumerate() {
+        if i % 2 == 0 {
+            x.take();
+        } else if let Some(x) = x.as_mut() {
+            x[0] += 10;
+        }
+    }
+    assert!(vec.iter().map(|x| x.map(|x| x[0])).eq([
+        None,
+        Some(11),
+        None,
+        None,
+        None,
+        Some(15),
+        None,
+        Some(17),
+        None,
+        None
+    ]));
+
+    // an `OptionProxy` moves the value out until it is dropped
+    vec.iter_mut().for_each(std::mem::forget);
+    assert!(vec.is_all_none());
+}
+
+#[test]
+fn count_some() {
+    let mut vec: VecOption<i32> = (0..200)
+        .map(|i| if i % 3 == 0 { Some(i) } else { None })
+        .collect();
+
+    assert_eq!(vec.count_some(), 67);
+    assert_eq!(vec.count_none(), 133);
+    assert!(!vec.is_all_some() && !vec.is_all_none());
+
+    let slice = vec.as_slice().get(1..3).unwrap();
+    assert!(slice.is_all_none() && !slice.is_all_some());
+    assert_eq!(vec.as_slice().get(3..199).unwrap().count_some(), 66);
+
+    vec.set_all_none();
+    assert!(vec.is_all_none());
+    assert_eq!(vec.count_none(), 200);
+
+    let vec: VecOption<i32> = (0..100).map(Some).collect();
+    assert!(vec.is_all_some());
+    assert!(VecOption::<u8>::new().is_all_some() && VecOption::<u8>::new().is_all_none());
+}
+
+#[test]
+fn rank_select() {
+    let model: Vec<_> = (0..500)
+        .map(|i| {
+            if i % 5 == 1 || (200..300).contains(&i) {
+                Some(i)
+            } else {
+                None
+            }
+        })
+        .collect();
+    let vec: VecOption<i32> = model.iter().cloned().collect();
+    let some: Vec<_> = (0..500).filter(|&i| model[i].is_some()).collect();
+
+    let index = vec.rank_select();
+    assert_eq!(index.count_some(), some.len());
+
+    for i in 0..=500 {
+        let rank = some.iter().filter(|&&j| j < i).count();
+        assert_eq!(vec.rank_some(i), rank);
+        assert_eq!(index.rank_some(i), rank);
+    }
+
+    for k in 0..=some.len() {
+        assert_eq!(vec.select_some(k), some.get(k).cloned());
+        assert_eq!(index.select_some(k), some.get(k).cloned());
+    }
+
+    // unaligned slices count from the start of the slice
+    let slice = vec.as_slice().get(7..450).unwrap();
+    let index = slice.rank_select();
+    for k in 0..slice.count_some() {
+        let i = slice.select_some(k).unwrap();
+        assert_eq!(index.select_some(k), Some(i));
+        assert_eq!(index.rank_some(i), k);
+        assert!(slice.get(i).unwrap().is_some());
+    }
+    assert_eq!(slice.select_some(slice.count_some()), None);
+
+    assert!(std::panic::catch_unwind(|| vec.rank_select().rank_some(501)).is_err());
+}
+
+#[test]
+fn search() {
+    let mut vec: VecOption<_> = (0..300)
+        .map(|i| if i >= 70 && i != 250 { Some(i) } else { None })
+        .collect();
+
+    assert_eq!(vec.first_some(), Some(70));
+    assert_eq!(vec.first_none(), Some(0));
+    assert_eq!(vec.last_some(), Some(299));
+    assert_eq!(vec.last_none(), Some(250));
+
+    assert_eq!(vec.next_some(5), Some(70));
+    assert_eq!(vec.next_some(250), Some(251));
+    assert_eq!(vec.next_none(69), Some(69));
+    assert_eq!(vec.next_none(70), Some(250));
+    assert_eq!(vec.next_none(251), None);
+    assert_eq!(vec.next_some(300), None);
+
+    assert_eq!(vec.prev_some(250), Some(249));
+    assert_eq!(vec.prev_some(70), None);
+    assert_eq!(vec.prev_none(250), Some(69));
+    assert_eq!(vec.prev_none(251), Some(250));
+    assert_eq!(vec.prev_none(1000), Some(250));
+
+    // the indices are relative to the start of the slice
+    let slice = vec.as_slice().get(65..260).unwrap();
+    assert_eq!(slice.first_some(), Some(5));
+    assert_eq!(slice.next_none(5), Some(185));
+    assert_eq!(slice.prev_none(185), Some(4));
+    assert_eq!(slice.last_some(), Some(194));
+
+    // use the vector as a free list
+    let slot = vec.first_none().unwrap();
+    vec.replace(slot, 1000);
+    assert_eq!(vec.first_none(), Some(1));
+
+    vec.clear();
+    assert_eq!(vec.first_none(), None);
+    assert_eq!(vec.last_some(), None);
+}
+
+#[test]
+fn runs() {
+    let ranges = [3..5, 60..130, 131..132, 190..256, 300..301];
+    let vec: VecOption<usize> = (0..301)
+        .map(|i| {
+            if ranges.iter().any(|r| r.contains(&i)) {
+                Some(i)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    assert!(vec.some_runs().eq(ranges.iter().cloned()));
+    assert!(vec.some_runs().rev().eq(ranges.iter().rev().cloned()));
+    assert!(vec
+        .none_runs()
+        .eq([0..3, 5..60, 130..131, 132..190, 256..300].iter().cloned()));
+
+    let mut runs = vec.none_runs();
+    assert_eq!(runs.next(), Some(0..3));
+    assert_eq!(runs.next_back(), Some(256..300));
+    assert_eq!(runs.next_back(), Some(132..190));
+    assert_eq!(runs.next(), Some(5..60));
+    assert_eq!(runs.next(), Some(130..131));
+    assert_eq!(runs.next_back(), None);
+
+    assert_eq!(vec.find_none_run(0), Some(0));
+    assert_eq!(vec.find_none_run(3), Some(0));
+    assert_eq!(vec.find_none_run(4), Some(5));
+    assert_eq!(vec.find_none_run(56), Some(132));
+    assert_eq!(vec.find_none_run(59), None);
+
+    // runs are clipped to the slice
+    let slice = vec.as_slice().get(100..200).unwrap();
+    assert!(slice
+        .some_runs()
+        .eq([0..30, 31..32, 90..100].iter().cloned()));
+    assert!(slice
+        .some_runs()
+        .rev()
+        .eq([90..100, 31..32, 0..30].iter().cloned()));
+    assert_eq!(slice.find_none_run(2), Some(32));
+}
+
+#[test]
+fn some_slices() {
+    let mut vec: VecOption<usize> = (0..200)
+        .map(|i| if i % 50 < 20 { Some(i) } else { None })
+        .collect();
+
+    let slices: Vec<_> = vec.some_slices().collect();
+    assert_eq!(slices.len(), 4);
+    for (j, &(start, slice)) in slices.iter().enumerate() {
+        assert_eq!(start, j * 50);
+        assert_eq!(slice, (start..start + 20).collect::<Vec<_>>().as_slice());
+    }
+    assert_eq!(vec.some_slices().next_back().unwrap().0, 150);
+
+    for (start, slice) in vec.some_slices_mut() {
+        slice.iter_mut().for_each(|x| *x -= start);
+    }
+    assert!(vec
+        .some_slices()
+        .all(|(_, slice)| slice == (0..20).collect::<Vec<_>>()));
+
+    assert_eq!(vec.as_some_slice(), None);
+    assert_eq!(
+        vec.as_slice()
+            .get(100..120)
+            .unwrap()
+            .as_some_slice()
+            .unwrap()
+            .len(),
+        20
+    );
+
+    let mut vec: VecOption<_> = (0..100).map(Some).collect();
+    vec.as_some_slice_mut().unwrap()[5] = 0;
+    assert_eq!(vec.as_some_slice().unwrap()[..6], [0, 1, 2, 3, 4, 0]);
+    assert_eq!(VecOption::<u8>::new().as_some_slice(), Some(&[][..]));
+}
+
+#[test]
+fn into_vec() {
+    let vec: VecOption<_> = (0..100).map(|i| Some(i.to_string())).collect();
+    let ptr = vec.data.as_ptr() as *const String;
+    let vec = Vec::try_from(vec).unwrap();
+    assert_eq!(vec.as_ptr(), ptr);
+    assert!(vec
+        .iter()
+        .eq((0..100).map(|i| i.to_string()).collect::<Vec<_>>().iter()));
+
+    let mut vec = VecOption::from(vec);
+    vec.replace(50, None);
+    let vec = Vec::<String>::try_from(vec).unwrap_err();
+    assert_eq!(vec.count_none(), 1);
+
+    // `Option<String>` has the same layout as `String`, so the allocation is reused
+    let ptr = vec.data.as_ptr() as *const Option<String>;
+    let options = Vec::<Option<String>>::from(vec);
+    assert_eq!(options.as_ptr(), ptr);
+    assert_eq!(options[49].as_deref(), Some("49"));
+    assert_eq!(options[50], None);
+
+    let vec: VecOption<u32> = vec![Some(1), None, Some(3)].into();
+    assert_eq!(Vec::from(vec.clone()), [Some(1), None, Some(3)]);
+
+    let (data, flag) = vec.into_boxed_parts();
+    assert_eq!(data.len(), 3);
+    assert_eq!(*flag, [0b101]);
+    assert_eq!(unsafe { data[2].assume_init() }, 3);
+
+    // the data is not copied if there is no spare capacity
+    let mut vec: VecOption<u8> = (0..200)
+        .map(|i| if i % 3 == 0 { Some(i) } else { None })
+        .collect();
+    vec.pop();
+    vec.shrink_to_fit();
+    let ptr = vec.data.as_ptr();
+
+    let (data, flag) = vec.into_boxed_parts();
+    let bits = usize::BITS as usize;
+    assert_eq!(data.as_ptr(), ptr);
+    assert_eq!(data.len(), 199);
+    assert_eq!(flag.len(), 199_usize.div_ceil(bits));
+    assert!((0..199).all(|i| (flag[i / bits] >> (i % bits) & 1 == 1) == (i % 3 == 0)));
+    // the bits past the length are zeroed
+    assert_eq!(flag[199 / bits] >> (199 % bits), 0);
+}
+
+#[test]
+fn from() {
+    // `Option<String>` has the same size as `String`, so the allocation is reused
+    let options: Vec<_> = (0..100)
+        .map(|i| {
+            if i % 3 == 0 {
+                None
+            } else {
+                Some(i.to_string())
+            }
+        })
+        .collect();
+    let copy = options.clone();
+    let ptr = copy.as_ptr() as *const String;
+    let vec = VecOption::from(copy);
+    assert_eq!(vec.data.as_ptr() as *const String, ptr);
+    assert!(vec.iter().eq(options.iter().map(Option::as_ref)));
+
+    // `Option<u32>` is twice the size of `u32`, so the capacity doubles
+    let options: Vec<_> = (0..100u32)
+        .map(|i| if i % 3 == 0 { None } else { Some(i) })
+        .collect();
+    let copy = options.clone();
+    let (ptr, cap) = (copy.as_ptr() as *const u32, copy.capacity());
+    let vec = VecOption::from(copy);
+    assert_eq!(vec.data.as_ptr() as *const u32, ptr);
+    assert_eq!(vec.capacity().data, 2 * cap);
+    assert!(vec.iter().eq(options.iter().map(Option::as_ref)));
+
+    // `Option<[u8; 3]>` has size 4, which can't be split into `[u8; 3]`s
+    let options: Vec<_> = (0..7u8).map(|i| Some([i; 3])).collect::<Vec<_>>();
+    assert!(VecOption::from(options.clone())
+        .iter()
+        .eq(options.iter().map(Option::as_ref)));
+
+    assert!(VecOption::from(vec![Some(()), None])
+        .iter()
+        .eq([Some(&()), None]));
+
+    assert_eq!(VecOption::from([Some(1), None]), [Some(1), None]);
+    assert_eq!(VecOption::from([1, 2]), [Some(1), Some(2)]);
+    assert_eq!(VecOption::from(&[None, Some(1)][..]), [None, Some(1)]);
+    assert_eq!(
+        VecOption::from(vec![Some(1), None].into_boxed_slice()),
+        [Some(1), None]
+    );
+    assert_eq!(
+        (0..3).collect::<VecOption<_>>(),
+        [Some(0), Some(1), Some(2)]
+    );
+}
+
+#[test]
+fn extend() {
+    let mut vec = VecOption::new();
+    vec.extend((0..100).map(|i| if i % 2 == 0 { Some(i) } else { None }));
+    vec.extend(100..150);
+    // the size hint of `filter` has a lower bound of zero
+    vec.extend((150..300).filter(|i| i % 3 != 0));
+    vec.extend_from_slice(&[300, 301]);
+
+    let expected: Vec<_> = (0..100)
+        .map(|i| if i % 2 == 0 { Some(i) } else { None })
+        .chain((100..150).map(Some))
+        .chain((150..300).filter(|i| i % 3 != 0).map(Some))
+        .chain([Some(300), Some(301)])
+        .collect();
+    assert!(vec.iter().eq(expected.iter().map(Option::as_ref)));
+
+    // the elements before a panic are kept
+    let mut vec: VecOption<String> = VecOption::new();
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        vec.extend((0..100).map(|i| if i == 70 { panic!() } else { i.to_string() }))
+    }));
+    assert!(res.is_err());
+    assert_eq!(vec.len(), 70);
+    assert_eq!(vec.get(69), Some(Some(&String::from("69"))));
+
+    vec.extend_from_slice(&[String::from("a")]);
+    assert_eq!(vec.len(), 71);
+}
+
+#[test]
+fn extend_block_boundaries() {
+    use bit_vec::BITS;
+
+    // an empty extend doesn't touch the flags
+    let mut vec = VecOption::<u8>::new();
+    vec.extend_from_slice(&[]);
+    vec.extend(std::iter::empty::<u8>());
+    vec.extend(std::iter::empty::<Option<u8>>());
+    assert!(vec.is_empty());
+
+    // each extend ends exactly on a block boundary
+    let values: Vec<_> = (0..BITS).collect();
+    let mut vec = VecOption::with_capacity(BITS);
+    vec.extend_from_slice(&values);
+    assert_eq!(vec.len(), BITS);
+    assert!(vec.is_all_some());
+
+    vec.extend(values.iter().map(|&i| Some(i).filter(|i| i % 2 == 0)));
+    vec.extend(values.iter().cloned());
+    vec.extend_from_slice(&[]);
+    assert_eq!(vec.len(), 3 * BITS);
+    assert_eq!(vec.count_some(), 2 * BITS + BITS / 2);
+    assert!(vec
+        .iter()
+        .skip(BITS)
+        .take(BITS)
+        .eq(values.iter().map(|i| Some(i).filter(|i| *i % 2 == 0))));
+}
+
+#[test]
+fn drop_some() {
+    use std::rc::Rc;
+
+    let rc = Rc::new(());
+
+    let mut vec: VecOption<Rc<()>> = VecOption::with_len_none(1000);
+    for &i in &[3, 64, 500, 700, 999] {
+        vec.replace(i, rc.clone());
+    }
+    assert_eq!(Rc::strong_count(&rc), 6);
+
+    vec.truncate(600);
+    assert_eq!(Rc::strong_count(&rc), 4);
+    assert_eq!(vec.count_some(), 3);
+
+    vec.set_all_none();
+    assert_eq!(Rc::strong_count(&rc), 1);
+    assert_eq!(vec.len(), 600);
+
+    vec.replace(599, rc.clone());
+    vec.replace(7, rc.clone());
+    drop(vec);
+    assert_eq!(Rc::strong_count(&rc), 1);
+
+    // a panicking destructor leaves the rest of the elements in the vector
+    struct PanicOnDrop(bool);
+
+    impl Drop for PanicOnDrop {
+        fn drop(&mut self) {
+            if self.0 {
+                panic!()
+            }
+        }
+    }
+
+    let mut vec: VecOption<_> = VecOption::with_len_none(300);
+    vec.replace(10, PanicOnDrop(false));
+    vec.replace(150, PanicOnDrop(true));
+    vec.replace(299, PanicOnDrop(false));
+
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| vec.truncate(5)));
+    assert!(res.is_err());
+    assert_eq!(vec.len(), 300);
+    assert!(vec.some_runs().eq(Some(299..300)));
+    vec.clear();
+}
+
+#[test]
+fn slice_mut() {
+    let mut model: Vec<_> = (0..300)
+        .map(|i| {
+            if i % 3 == 0 || i % 7 == 0 {
+                Some(i.to_string())
+            } else {
+                None
+            }
+        })
+        .collect();
+    let mut vec: VecOption<String> = model.iter().cloned().collect();
+
+    let check = |vec: &VecOption<String>, model: &Vec<Option<String>>| {
+        assert!(vec.iter().eq(model.iter().map(Option::as_ref)));
+    };
+
+    vec.as_mut_slice().get_mut(5..290).unwrap().reverse();
+    model[5..290].reverse();
+    check(&vec, &model);
+
+    vec.as_mut_slice().get_mut(3..200).unwrap().rotate_left(70);
+    model[3..200].rotate_left(70);
+    check(&vec, &model);
+
+    vec.as_mut_slice().get_mut(1..).unwrap().rotate_right(131);
+    model[1..].rotate_right(131);
+    check(&vec, &model);
+
+    vec.as_mut_slice().swap(2, 299);
+    model.swap(2, 299);
+    check(&vec, &model);
+
+    let (mut left, right) = vec.as_mut_slice().split_at_mut(150).ok().unwrap();
+    left.get_mut(7..107)
+        .unwrap()
+        .swap_with_slice(right.into_get_mut(13..113).unwrap());
+    let (l, r) = model.split_at_mut(150);
+    l[7..107].swap_with_slice(&mut r[13..113]);
+    check(&vec, &model);
+
+    vec.as_mut_slice()
+        .get_mut(10..20)
+        .unwrap()
+        .fill(Some(String::from("x")));
+    model[10..20].fill(Some(String::from("x")));
+    vec.as_mut_slice().get_mut(30..90).unwrap().set_none();
+    model[30..90].fill(None);
+    let mut counter = 0;
+    vec.as_mut_slice().get_mut(100..103).unwrap().fill_with(|| {
+        counter += 1;
+        Some(counter.to_string())
+    });
+    model[100..103].clone_from_slice(&[Some("1".into()), Some("2".into()), Some("3".into())]);
+    check(&vec, &model);
+
+    // the clones written before a panic are kept
+    #[derive(Debug)]
+    struct PanicOnClone(std::rc::Rc<std::cell::Cell<u32>>);
+
+    impl Clone for PanicOnClone {
+        fn clone(&self) -> Self {
+            self.0.set(self.0.get() + 1);
+            if self.0.get() == 5 {
+                panic!()
+            }
+            PanicOnClone(self.0.clone())
+        }
+    }
+
+    let mut vec: VecOption<PanicOnClone> = VecOption::with_len_none(10);
+    let value = PanicOnClone(Default::default());
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        vec.as_mut_slice().fill(Some(value.clone()))
+    }));
+    assert!(res.is_err());
+    assert_eq!(vec.count_some(), 3);
+}
+
+#[test]
+fn copy_from_slice() {
+    let model: Vec<_> = (0..200u32)
+        .map(|i| if i % 3 == 0 { None } else { Some(i) })
+        .collect();
+    let src: VecOption<u32> = model.iter().cloned().collect();
+
+    let mut vec: VecOption<u32> = VecOption::with_len_none(300);
+    vec.as_mut_slice()
+        .get_mut(11..161)
+        .unwrap()
+        .copy_from_slice(src.as_slice().get(5..155).unwrap());
+    assert!(vec.iter().take(11).all(|x| x.is_none()));
+    assert!(vec
+        .iter()
+        .skip(11)
+        .take(150)
+        .eq(model[5..155].iter().map(Option::as_ref)));
+    assert!(vec.iter().skip(161).all(|x| x.is_none()));
+
+    let strings: VecOption<String> = model.iter().map(|x| x.map(|x| x.to_string())).collect();
+    let copy = strings.as_slice().get(3..190).unwrap().to_vec_option();
+    assert!(copy.iter().eq(strings.iter().skip(3).take(187)));
+
+    let mut dest: VecOption<String> = (0..187).map(|i| Some(i.to_string())).collect();
+    let ptr = dest.get(1).unwrap().unwrap().as_ptr();
+    dest.as_mut_slice()
+        .clone_from_slice(strings.as_slice().get(3..190).unwrap());
+    assert_eq!(dest, copy);
+    // the `String` was reused, instead of being replaced
+    assert_eq!(dest.get(1).unwrap().unwrap().as_ptr(), ptr);
+
+    let res = std::panic::catch_unwind(move || {
+        vec.as_mut_slice().copy_from_slice(src.as_slice());
+    });
+    assert!(res.is_err());
+}
+
+#[test]
+fn clone() {
+    let vec: VecOption<u64> = (0..300)
+        .map(|i| if i % 4 == 0 { None } else { Some(i) })
+        .collect();
+
+    let copy = vec.clone_copy();
+    assert_eq!(copy, vec);
+    assert_eq!(vec.clone(), vec);
+
+    let mut dest: VecOption<u64> = VecOption::with_capacity(400);
+    let cap = dest.capacity();
+    dest.clone_from_copy(&vec);
+    assert_eq!(dest, vec);
+    assert_eq!(dest.capacity(), cap);
+
+    let strings: VecOption<String> = vec.iter().map(|x| x.map(|x| x.to_string())).collect();
+    assert_eq!(strings.clone(), strings);
+
+    // both buffers and the existing strings are reused
+    let mut dest: VecOption<String> = (0..400).map(|i| Some(i.to_string())).collect();
+    let cap = dest.capacity();
+    let ptr = dest.get(1).unwrap().unwrap().as_ptr();
+    dest.clone_from(&strings);
+    assert_eq!(dest, strings);
+    assert_eq!(dest.capacity(), cap);
+    assert_eq!(dest.get(1).unwrap().unwrap().as_ptr(), ptr);
+
+    let mut dest: VecOption<String> = VecOption::new();
+    dest.clone_from(&strings);
+    assert_eq!(dest, strings);
+}
@@ -1,7 +1,9 @@
-use crate::bit_vec::slice::{BitSlice, BitSliceMut};
-use crate::{OptionProxy, VecOption};
+use crate::bit_vec::rank::RankIndex;
+use crate::bit_vec::slice::{BitSlice, BitSliceMut, Ones};
+use crate::{LazyOptionProxy, OptionProxy, Try, VecOption};
 
-use std::ops::Deref;
+use std::convert::Infallible;
+use std::ops::{ControlFlow, Deref};
 
 use std::marker::PhantomData;
 use std::mem::MaybeUninit;
@@ -86,6 +88,26 @@ impl<T> VecOption<T> {
     }
 }
 
+impl<T> crate::IntoIter<T> {
+    /// Returns the remaining elements of this iterator as a slice
+    pub fn as_slice(&self) -> Slice<'_, T> {
+        Slice {
+            data: NonNull::from(self.data.as_slice()).cast(),
+            flag: self.flag.as_slice(),
+            lt: PhantomData,
+        }
+    }
+
+    /// Returns the remaining elements of this iterator as a mutable slice
+    pub fn as_mut_slice(&mut self) -> SliceMut<'_, T> {
+        SliceMut {
+            data: NonNull::from(self.data.as_mut_slice()).cast(),
+            flag: self.flag.as_mut_slice(),
+            lt: PhantomData,
+        }
+    }
+}
+
 impl<'a, T> SliceMut<'a, T> {
     pub fn into_slice(self) -> Slice<'a, T> {
         *self
@@ -113,6 +135,9 @@ impl<'a, T> Slice<'a, T> {
         self.flag.is_empty()
     }
 
+    /// # Safety
+    ///
+    /// `index` must be in bounds
     pub unsafe fn get_unchecked<I: SliceIndex<Self>>(self, index: I) -> I::Output {
         index.get_unchecked(self)
     }
@@ -125,6 +150,152 @@ impl<'a, T> Slice<'a, T> {
         self.into_iter()
     }
 
+    /// Clones the elements into a new `VecOption<T>`, only visiting the `Some` elements
+    pub fn to_vec_option(self) -> VecOption<T>
+    where
+        T: Clone,
+    {
+        let mut vec = VecOption::with_len_none(self.len());
+
+        for (i, value) in self.iter_some() {
+            vec.replace(i, value.clone());
+        }
+
+        vec
+    }
+
+    /// Counts the number of `Some` elements in the slice
+    pub fn count_some(self) -> usize {
+        self.flag.count_ones()
+    }
+
+    /// Counts the number of `None` elements in the slice
+    pub fn count_none(self) -> usize {
+        self.flag.count_zeros()
+    }
+
+    /// Returns true if every element of the slice is `Some`
+    pub fn is_all_some(self) -> bool {
+        self.flag.is_all_ones()
+    }
+
+    /// Returns true if every element of the slice is `None`
+    pub fn is_all_none(self) -> bool {
+        self.flag.is_all_zeros()
+    }
+
+    /// Finds the index of the first `Some` element
+    pub fn first_some(self) -> Option<usize> {
+        self.flag.find(0, true)
+    }
+
+    /// Finds the index of the first `None` element
+    pub fn first_none(self) -> Option<usize> {
+        self.flag.find(0, false)
+    }
+
+    /// Finds the index of the last `Some` element
+    pub fn last_some(self) -> Option<usize> {
+        self.flag.rfind(self.len(), true)
+    }
+
+    /// Finds the index of the last `None` element
+    pub fn last_none(self) -> Option<usize> {
+        self.flag.rfind(self.len(), false)
+    }
+
+    /// Finds the index of the first `Some` element at or after `from`
+    pub fn next_some(self, from: usize) -> Option<usize> {
+        self.flag.find(from, true)
+    }
+
+    /// Finds the index of the first `None` element at or after `from`
+    pub fn next_none(self, from: usize) -> Option<usize> {
+        self.flag.find(from, false)
+    }
+
+    /// Finds the index of the last `Some` element before `from`
+    pub fn prev_some(self, from: usize) -> Option<usize> {
+        self.flag.rfind(from, true)
+    }
+
+    /// Finds the index of the last `None` element before `from`
+    pub fn prev_none(self, from: usize) -> Option<usize> {
+        self.flag.rfind(from, false)
+    }
+
+    /// Finds the first index that starts a run of at least `k` `None` elements
+    pub fn find_none_run(self, k: usize) -> Option<usize> {
+        if k == 0 {
+            return Some(0);
+        }
+
+        self.none_runs()
+            .find(|run| run.len() >= k)
+            .map(|run| run.start)
+    }
+
+    /// Returns an iterator over the ranges of each maximal run of `None` elements
+    pub fn none_runs(self) -> Runs<'a> {
+        Runs::new(self.flag, false)
+    }
+
+    /// Returns an iterator over the ranges of each maximal run of `Some` elements
+    pub fn some_runs(self) -> Runs<'a> {
+        Runs::new(self.flag, true)
+    }
+
+    /// Returns an iterator over each maximal run of `Some` elements as a slice,
+    /// along with the index of the start of the run
+    pub fn some_slices(self) -> SomeSlices<'a, T> {
+        SomeSlices {
+            data: self.data,
+            runs: self.some_runs(),
+            lt: PhantomData,
+        }
+    }
+
+    /// Returns the elements as a slice if every element is `Some`
+    pub fn as_some_slice(self) -> Option<&'a [T]> {
+        if self.is_all_some() {
+            // all of the elements are initialized
+            unsafe { Some(std::slice::from_raw_parts(self.data.as_ptr(), self.len())) }
+        } else {
+            None
+        }
+    }
+
+    /// Counts the number of `Some` elements before `index`, this scans the flags
+    /// one block at a time, use `rank_select` when making many queries
+    ///
+    /// Panics if `index > len`
+    pub fn rank_some(self, index: usize) -> usize {
+        self.flag.rank(index)
+    }
+
+    /// Finds the index of the `k`-th `Some` element (counting from zero), this scans
+    /// the flags one block at a time, use `rank_select` when making many queries
+    pub fn select_some(self, k: usize) -> Option<usize> {
+        self.flag.select(k)
+    }
+
+    /// Builds an index for fast `rank_some` and `select_some` queries,
+    /// use this when making many queries on a slice that doesn't change
+    pub fn rank_select(self) -> RankSelect<'a> {
+        RankSelect {
+            index: self.flag.rank_index(),
+        }
+    }
+
+    /// returns an iterator over the `Some` elements of the slice and their indices
+    pub fn iter_some(self) -> IterSome<'a, T> {
+        IterSome {
+            data: self.data,
+            ones: self.flag.ones(),
+            lt: PhantomData,
+        }
+    }
+
     pub fn split_at(self, index: usize) -> Option<(Self, Self)> {
         if index <= self.len() {
             unsafe { Some(self.split_at_unchecked(index)) }
@@ -149,6 +320,9 @@ impl<'a, T> Slice<'a, T> {
         }
     }
 
+    /// # Safety
+    ///
+    /// `index` must be less than or equal to `self.len()`
     pub unsafe fn split_at_unchecked(self, index: usize) -> (Self, Self) {
         let data = NonNull::new_unchecked(self.data.as_ptr().add(index));
 
@@ -196,6 +370,9 @@ impl<'a, T> SliceMut<'a, T> {
         }
     }
 
+    /// # Safety
+    ///
+    /// `index` must be in bounds
     pub unsafe fn into_get_unchecked_mut<I: SliceIndexMut<Self>>(self, index: I) -> I::Output {
         index.get_unchecked_mut(self)
     }
@@ -204,6 +381,9 @@ impl<'a, T> SliceMut<'a, T> {
         index.get_mut(self)
     }
 
+    /// # Safety
+    ///
+    /// `index` must be in bounds
     pub unsafe fn get_unchecked_mut<'b, I: SliceIndexMut<SliceMut<'b, T>>>(
         &'b mut self,
         index: I,
@@ -218,10 +398,89 @@ impl<'a, T> SliceMut<'a, T> {
         index.get_mut(self.as_mut())
     }
 
+    /// Returns a mutable reference to the element at `index` if it is `Some`
+    ///
+    /// The reference points directly into the slice, so the value is not moved
+    pub fn into_some_mut(self, index: usize) -> Option<&'a mut T> {
+        if self.flag.get(index)? {
+            // the flag is set, so the element is initialized
+            unsafe { Some(&mut *self.data.as_ptr().add(index)) }
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the element at `index` if it is `Some`
+    ///
+    /// The reference points directly into the slice, so the value is not moved
+    pub fn get_some_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.as_mut().into_some_mut(index)
+    }
+
+    /// Returns a lazy proxy to the element at `index` or None if out of bounds.
+    pub fn into_lazy_mut(self, index: usize) -> Option<LazyOptionProxy<'a, T>> {
+        if index < self.len() {
+            unsafe {
+                let flag = index.get_unchecked_mut(self.flag);
+                let data = &mut *self.data.cast::<MaybeUninit<T>>().as_ptr().add(index);
+
+                Some(LazyOptionProxy::new(flag, data))
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Returns a lazy proxy to the element at `index` or None if out of bounds.
+    pub fn get_lazy_mut(&mut self, index: usize) -> Option<LazyOptionProxy<'_, T>> {
+        self.as_mut().into_lazy_mut(index)
+    }
+
     pub fn iter_mut(self) -> IterMut<'a, T> {
         self.into_iter()
     }
 
+    /// Returns an iterator over lazy proxies to the elements of the slice,
+    /// the values are only moved if an option is reassigned
+    pub fn iter_lazy_mut(self) -> IterLazyMut<'a, T> {
+        IterLazyMut { slice: self }
+    }
+
+    /// Returns an iterator over each maximal run of `Some` elements as a mutable slice,
+    /// along with the index of the start of the run
+    pub fn some_slices_mut(self) -> SomeSlicesMut<'a, T> {
+        SomeSlicesMut {
+            data: self.data,
+            runs: Runs::new(self.flag.into_slice(), true),
+            lt: PhantomData,
+        }
+    }
+
+    /// Returns the elements as a mutable slice if every element is `Some`
+    pub fn as_some_slice_mut(self) -> Option<&'a mut [T]> {
+        if self.is_all_some() {
+            // all of the elements are initialized
+            unsafe {
+                Some(std::slice::from_raw_parts_mut(
+                    self.data.as_ptr(),
+                    self.len(),
+                ))
+            }
+        } else {
+            None
+        }
+    }
+
+    /// returns an iterator over mutable references to the `Some` elements of
+    /// the slice and their indices
+    pub fn iter_some_mut(self) -> IterSomeMut<'a, T> {
+        IterSomeMut {
+            data: self.data,
+            ones: self.flag.into_slice().ones(),
+            lt: PhantomData,
+        }
+    }
+
     pub fn split_at_mut(self, index: usize) -> Result<(Self, Self), Self> {
         if index <= self.len() {
             unsafe { Ok(self.split_at_mut_unchecked(index)) }
@@ -249,6 +508,9 @@ impl<'a, T> SliceMut<'a, T> {
         }
     }
 
+    /// # Safety
+    ///
+    /// `index` must be less than or equal to `self.len()`
     pub unsafe fn split_at_mut_unchecked(self, index: usize) -> (Self, Self) {
         let data = NonNull::new_unchecked(self.data.as_ptr().add(index));
 
@@ -307,49 +569,273 @@ impl<'a, T> SliceMut<'a, T> {
         }
     }
 
-    // pub fn set(&mut self, index: usize, value: bool) {
-    //     assert!(index < self.len, "Index is out of bounds!");
+    /// Calls `f` with a mutable reference to the element at `index`,
+    /// returns `None` if `index` is out of bounds
+    ///
+    /// If `f` panics, the element will be set to `None`
+    pub fn with_mut<R, F: FnOnce(&mut Option<T>) -> R>(&mut self, index: usize, f: F) -> Option<R> {
+        if index < self.len() {
+            unsafe { Some(self.with_mut_unchecked(index, f)) }
+        } else {
+            None
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `index` must be in bounds
+    unsafe fn with_mut_unchecked<R, F: FnOnce(&mut Option<T>) -> R>(
+        &mut self,
+        index: usize,
+        f: F,
+    ) -> R {
+        let data = self.data.as_ptr().add(index);
+
+        // take the value out before calling `f`, so that if `f` panics
+        // the value will be dropped and the element will be left as `None`
+        let mut value = if self.flag.get_unchecked(index) {
+            self.flag.set(index, false);
+
+            // flag corrosponds to data
+            Some(data.read())
+        } else {
+            None
+        };
+
+        let out = f(&mut value);
+
+        if let Some(value) = value {
+            // data is logically uninitialized, so use write to
+            // prevent dropping it
+            data.write(value);
+            self.flag.set(index, true);
+        }
+
+        out
+    }
+
+    /// Returns an iterator that takes every `Some` value that matches `pred` out of the slice,
+    /// leaving `None` in its place, and yields it along with its index
+    ///
+    /// Only the elements that are `Some` are visited, and the elements that are
+    /// not visited because the iterator was dropped early are left in place
+    pub fn take_if<F: FnMut(&T) -> bool>(&mut self, pred: F) -> TakeIf<'_, T, F> {
+        TakeIf::new(self.as_mut(), pred)
+    }
+
+    /// Drops every `Some` value that matches `pred`, leaving `None` in its place
+    ///
+    /// Only the elements that are `Some` are visited
+    pub fn clear_if<F: FnMut(&T) -> bool>(&mut self, pred: F) {
+        self.take_if(pred).for_each(drop)
+    }
+
+    /// Folds every element into an accumulator by applying an operation,
+    /// returning the final result, stopping early if `f` short-circuits
+    ///
+    /// If `f` panics, the element it was called with will be set to `None`
+    pub fn try_fold<A, R, F>(&mut self, init: A, mut f: F) -> R
+    where
+        F: FnMut(A, &mut Option<T>) -> R,
+        R: Try<Output = A>,
+    {
+        let mut acc = init;
+
+        for index in 0..self.len() {
+            // index is in bounds
+            let res = unsafe { self.with_mut_unchecked(index, |value| f(acc, value)) };
+
+            acc = match res.branch() {
+                ControlFlow::Continue(acc) => acc,
+                ControlFlow::Break(residual) => return R::from_residual(residual),
+            };
+        }
+
+        R::from_output(acc)
+    }
+
+    /// Folds every element into an accumulator by applying an operation,
+    /// returning the final result
+    ///
+    /// If `f` panics, the element it was called with will be set to `None`
+    pub fn fold<A, F: FnMut(A, &mut Option<T>) -> A>(&mut self, init: A, mut f: F) -> A {
+        let res = self.try_fold(init, |acc, value| Ok::<_, Infallible>(f(acc, value)));
+
+        match res {
+            Ok(acc) => acc,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Calls `f` on every element, stopping early if `f` short-circuits
+    ///
+    /// If `f` panics, the element it was called with will be set to `None`
+    pub fn try_for_each<R, F>(&mut self, mut f: F) -> R
+    where
+        F: FnMut(&mut Option<T>) -> R,
+        R: Try<Output = ()>,
+    {
+        self.try_fold((), |(), value| f(value))
+    }
 
-    //     let (slot, offset) = index_to_slot(index + self.offset as usize);
+    /// Calls `f` on every element
+    ///
+    /// If `f` panics, the element it was called with will be set to `None`
+    pub fn for_each<F: FnMut(&mut Option<T>)>(&mut self, mut f: F) {
+        self.fold((), |(), value| f(value))
+    }
 
-    //     let slot = unsafe { &mut *self.ptr.as_ptr().add(slot) };
+    /// Sets every element to `None`, dropping the `Some` elements and
+    /// skipping over blocks of flags that are not set
+    ///
+    /// Each flag is cleared before the element is dropped, so the slice stays
+    /// valid if a destructor panics
+    pub fn set_none(&mut self) {
+        if !std::mem::needs_drop::<T>() {
+            return self.flag.set_all(false);
+        }
 
-    //     set_bit(slot, offset, value);
-    // }
+        let mut index = 0;
 
-    // pub fn set_all(&mut self, value: bool) {
-    //     let block_value = if value { !0 } else { 0 };
+        while let Some(i) = self.flag.find(index, true) {
+            self.flag.set(i, false);
 
-    //     let (blocks, last) = index_to_slot(self.offset as usize + self.len);
-    //     let ptr = self.ptr.as_ptr();
+            // the flag was set, so the data is initialized
+            unsafe { self.data.as_ptr().add(i).drop_in_place() }
 
-    //     let (ptr, blocks) = if self.offset == 0 {
-    //         (ptr, blocks)
-    //     } else {
-    //         unsafe {
-    //             // first byte
-    //             for i in self.offset..8 {
-    //                 set_bit(&mut *ptr, i, value);
-    //             }
+            index = i + 1;
+        }
+    }
 
-    //             (ptr.add(1), blocks - 1)
-    //         }
-    //     };
+    /// Fills the slice with clones of `value`
+    pub fn fill(&mut self, value: Option<T>)
+    where
+        T: Clone,
+    {
+        /// Sets the flags of the elements that were written, even if `clone` panics
+        struct Guard<'b> {
+            flag: BitSliceMut<'b>,
+            len: usize,
+        }
 
-    //     unsafe {
-    //         // last byte
-    //         let ptr = ptr.add(blocks);
+        impl Drop for Guard<'_> {
+            fn drop(&mut self) {
+                // only the first `len` elements were written
+                unsafe { self.flag.get_unchecked_mut(..self.len).set_all(true) }
+            }
+        }
 
-    //         for i in 0..last {
-    //             set_bit(&mut *ptr, i, value);
-    //         }
-    //     }
+        self.set_none();
 
-    //     unsafe {
-    //         // middle bytes
-    //         std::ptr::write_bytes(ptr, block_value, blocks);
-    //     }
-    // }
+        let len = self.len();
+        let value = match value {
+            Some(value) if len != 0 => value,
+            _ => return,
+        };
+
+        let data = self.data.as_ptr();
+        let mut guard = Guard {
+            flag: self.flag.as_mut(),
+            len: 0,
+        };
+
+        // all of the elements are `None`, so they can be overwritten
+        unsafe {
+            for i in 0..len - 1 {
+                data.add(i).write(value.clone());
+                guard.len += 1;
+            }
+
+            data.add(len - 1).write(value);
+            guard.len += 1;
+        }
+    }
+
+    /// Fills the slice with values returned from `f`
+    pub fn fill_with<F: FnMut() -> Option<T>>(&mut self, mut f: F) {
+        for i in 0..self.len() {
+            self.replace(i, f());
+        }
+    }
+
+    /// The data of the slice, which is initialized wherever the flag is set
+    fn raw_data(&mut self) -> &mut [MaybeUninit<T>] {
+        unsafe { std::slice::from_raw_parts_mut(self.data.cast().as_ptr(), self.len()) }
+    }
+
+    /// Swaps two elements of the slice, panics if either index is out of bounds
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.raw_data().swap(a, b);
+        self.flag.swap(a, b);
+    }
+
+    /// Reverses the order of the elements in the slice
+    pub fn reverse(&mut self) {
+        self.raw_data().reverse();
+        self.flag.reverse();
+    }
+
+    /// Rotates the slice so that the element at `mid` becomes the first element
+    ///
+    /// Panics if `mid > len`
+    pub fn rotate_left(&mut self, mid: usize) {
+        self.raw_data().rotate_left(mid);
+        self.flag.rotate_left(mid);
+    }
+
+    /// Rotates the slice so that the last `k` elements become the first elements
+    ///
+    /// Panics if `k > len`
+    pub fn rotate_right(&mut self, k: usize) {
+        self.raw_data().rotate_right(k);
+        self.flag.rotate_left(self.len() - k);
+    }
+
+    /// Clones all of the elements from `src`, reusing the existing values
+    /// where both elements are `Some`
+    ///
+    /// Panics if the slices have different lengths
+    pub fn clone_from_slice(&mut self, src: Slice<'_, T>)
+    where
+        T: Clone,
+    {
+        assert_eq!(
+            self.len(),
+            src.len(),
+            "destination and source slices have different lengths"
+        );
+
+        for (i, value) in src.iter().enumerate() {
+            if let (Some(value), Some(dest)) = (value, self.get_some_mut(i)) {
+                dest.clone_from(value);
+            } else {
+                self.replace(i, value.cloned());
+            }
+        }
+    }
+
+    /// Copies all of the elements from `src`, the data is copied with a `memcpy`
+    /// and the flags are copied one block at a time
+    ///
+    /// Panics if the slices have different lengths
+    pub fn copy_from_slice(&mut self, src: Slice<'_, T>)
+    where
+        T: Copy,
+    {
+        self.flag.copy_from_slice(src.flag);
+
+        // `Copy` types don't need to be dropped, and a mutable slice can't
+        // overlap with a shared slice
+        unsafe { std::ptr::copy_nonoverlapping(src.data.as_ptr(), self.data.as_ptr(), src.len()) }
+    }
+
+    /// Swaps all of the elements with the elements of `other`
+    ///
+    /// Panics if the slices have different lengths
+    pub fn swap_with_slice(&mut self, mut other: SliceMut<'_, T>) {
+        self.raw_data().swap_with_slice(other.raw_data());
+        self.flag.swap_with_slice(&mut other.flag);
+    }
 }
 
 pub(crate) use seal::Seal;
@@ -362,6 +848,9 @@ pub trait SliceIndex<S>: Seal<S> {
 
     fn check(&self, slice: &S) -> bool;
 
+    /// # Safety
+    ///
+    /// `self.check(&slice)` must return true
     unsafe fn get_unchecked(self, slice: S) -> Self::Output;
 
     fn get(self, slice: S) -> Option<Self::Output> {
@@ -378,6 +867,9 @@ pub trait SliceIndexMut<S>: Seal<S> {
 
     fn check_mut(&self, slice: &S) -> bool;
 
+    /// # Safety
+    ///
+    /// `self.check_mut(&slice)` must return true
     unsafe fn get_unchecked_mut(self, slice: S) -> Self::Output;
 
     fn get_mut(self, slice: S) -> Option<Self::Output> {
@@ -706,7 +1198,7 @@ impl<'a, T> Iterator for IterMut<'a, T> {
     type Item = OptionProxy<'a, T>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let slice = std::mem::replace(&mut self.slice, Default::default());
+        let slice = std::mem::take(&mut self.slice);
 
         let (next, rest) = slice.split_first_mut().ok()?;
 
@@ -716,7 +1208,7 @@ impl<'a, T> Iterator for IterMut<'a, T> {
     }
 
     fn nth(&mut self, n: usize) -> Option<Self::Item> {
-        let slice = std::mem::replace(&mut self.slice, Default::default());
+        let slice = std::mem::take(&mut self.slice);
 
         self.slice = slice.into_get_mut(n..)?;
 
@@ -730,7 +1222,7 @@ impl<'a, T> Iterator for IterMut<'a, T> {
 
 impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
     fn next_back(&mut self) -> Option<Self::Item> {
-        let slice = std::mem::replace(&mut self.slice, Default::default());
+        let slice = std::mem::take(&mut self.slice);
 
         let (rest, next) = slice.split_last_mut().ok()?;
 
@@ -741,7 +1233,7 @@ impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
 
     #[cfg(feature = "nightly")]
     fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
-        let slice = std::mem::replace(&mut self.slice, Default::default());
+        let slice = std::mem::take(&mut self.slice);
 
         let index = slice.len().checked_sub(n)?;
 
@@ -754,6 +1246,369 @@ impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
 impl<T> ExactSizeIterator for IterMut<'_, T> {}
 impl<T> std::iter::FusedIterator for IterMut<'_, T> {}
 
+/// This struct is created by the `iter_lazy_mut` method on `VecOption` and `SliceMut`
+pub struct IterLazyMut<'a, T> {
+    slice: SliceMut<'a, T>,
+}
+
+impl<'a, T> IterLazyMut<'a, T> {
+    pub fn into_slice_mut(self) -> SliceMut<'a, T> {
+        self.slice
+    }
+
+    pub fn as_slice(&self) -> Slice<'_, T> {
+        self.slice.as_ref()
+    }
+}
+
+impl<'a, T> Iterator for IterLazyMut<'a, T> {
+    type Item = LazyOptionProxy<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let slice = std::mem::take(&mut self.slice);
+
+        let (next, rest) = slice.split_at_mut(1).ok()?;
+
+        self.slice = rest;
+
+        next.into_lazy_mut(0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.slice.len(), Some(self.slice.len()))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterLazyMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let slice = std::mem::take(&mut self.slice);
+
+        let index = slice.len().checked_sub(1)?;
+
+        // `index` is less than the length of the slice
+        let (rest, next) = unsafe { slice.split_at_mut_unchecked(index) };
+
+        self.slice = rest;
+
+        next.into_lazy_mut(0)
+    }
+}
+
+impl<T> ExactSizeIterator for IterLazyMut<'_, T> {}
+impl<T> std::iter::FusedIterator for IterLazyMut<'_, T> {}
+
+/// An index over the flags of a slice that maps between the index of an element,
+/// and its position among the `Some` elements
+///
+/// This struct is created by the `rank_select` method on `VecOption` and `Slice`,
+/// it borrows the flags so they can't be modified while it is alive
+#[derive(Clone)]
+pub struct RankSelect<'a> {
+    index: RankIndex<'a>,
+}
+
+impl RankSelect<'_> {
+    /// Counts the number of `Some` elements, in constant time
+    pub fn count_some(&self) -> usize {
+        self.index.count_ones()
+    }
+
+    /// Counts the number of `Some` elements before `index`, in constant time
+    ///
+    /// Panics if `index > len`
+    pub fn rank_some(&self, index: usize) -> usize {
+        self.index.rank(index)
+    }
+
+    /// Finds the index of the `k`-th `Some` element (counting from zero), in logarithmic time
+    pub fn select_some(&self, k: usize) -> Option<usize> {
+        self.index.select(k)
+    }
+}
+
+/// This struct is created by the `none_runs` and `some_runs` methods on `VecOption` and `Slice`
+#[derive(Clone)]
+pub struct Runs<'a> {
+    flag: BitSlice<'a>,
+    value: bool,
+    start: usize,
+    end: usize,
+}
+
+impl<'a> Runs<'a> {
+    fn new(flag: BitSlice<'a>, value: bool) -> Self {
+        Self {
+            flag,
+            value,
+            start: 0,
+            end: flag.len(),
+        }
+    }
+}
+
+impl Iterator for Runs<'_> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self
+            .flag
+            .find(self.start, self.value)
+            .filter(|&i| i < self.end)?;
+        let end = self
+            .flag
+            .find(start, !self.value)
+            .map_or(self.end, |i| i.min(self.end));
+
+        self.start = end;
+
+        Some(start..end)
+    }
+}
+
+impl DoubleEndedIterator for Runs<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let end = self
+            .flag
+            .rfind(self.end, self.value)
+            .filter(|&i| i >= self.start)?
+            + 1;
+        let start = self
+            .flag
+            .rfind(end, !self.value)
+            .map_or(self.start, |i| self.start.max(i + 1));
+
+        self.end = start;
+
+        Some(start..end)
+    }
+}
+
+impl std::iter::FusedIterator for Runs<'_> {}
+
+/// This struct is created by the `some_slices` method on `VecOption` and `Slice`
+pub struct SomeSlices<'a, T> {
+    data: NonNull<T>,
+    runs: Runs<'a>,
+    lt: PhantomData<&'a [T]>,
+}
+
+unsafe impl<T: Sync> Send for SomeSlices<'_, T> {}
+unsafe impl<T: Sync> Sync for SomeSlices<'_, T> {}
+
+impl<T> Clone for SomeSlices<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data,
+            runs: self.runs.clone(),
+            lt: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> Iterator for SomeSlices<'a, T> {
+    type Item = (usize, &'a [T]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let run = self.runs.next()?;
+
+        // all of the elements in the run are initialized
+        unsafe {
+            let data = self.data.as_ptr().add(run.start);
+
+            Some((run.start, std::slice::from_raw_parts(data, run.len())))
+        }
+    }
+}
+
+impl<T> DoubleEndedIterator for SomeSlices<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let run = self.runs.next_back()?;
+
+        // all of the elements in the run are initialized
+        unsafe {
+            let data = self.data.as_ptr().add(run.start);
+
+            Some((run.start, std::slice::from_raw_parts(data, run.len())))
+        }
+    }
+}
+
+impl<T> std::iter::FusedIterator for SomeSlices<'_, T> {}
+
+/// This struct is created by the `some_slices_mut` method on `VecOption` and `SliceMut`
+pub struct SomeSlicesMut<'a, T> {
+    data: NonNull<T>,
+    runs: Runs<'a>,
+    lt: PhantomData<&'a mut [T]>,
+}
+
+unsafe impl<T: Send> Send for SomeSlicesMut<'_, T> {}
+unsafe impl<T: Sync> Sync for SomeSlicesMut<'_, T> {}
+
+impl<'a, T> Iterator for SomeSlicesMut<'a, T> {
+    type Item = (usize, &'a mut [T]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let run = self.runs.next()?;
+
+        // all of the elements in the run are initialized, and the runs don't overlap
+        unsafe {
+            let data = self.data.as_ptr().add(run.start);
+
+            Some((run.start, std::slice::from_raw_parts_mut(data, run.len())))
+        }
+    }
+}
+
+impl<T> DoubleEndedIterator for SomeSlicesMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let run = self.runs.next_back()?;
+
+        // all of the elements in the run are initialized, and the runs don't overlap
+        unsafe {
+            let data = self.data.as_ptr().add(run.start);
+
+            Some((run.start, std::slice::from_raw_parts_mut(data, run.len())))
+        }
+    }
+}
+
+impl<T> std::iter::FusedIterator for SomeSlicesMut<'_, T> {}
+
+/// This struct is created by the `iter_some` method on `VecOption` and `Slice`
+pub struct IterSome<'a, T> {
+    data: NonNull<T>,
+    ones: Ones<'a>,
+    lt: PhantomData<&'a [T]>,
+}
+
+unsafe impl<T: Sync> Send for IterSome<'_, T> {}
+unsafe impl<T: Sync> Sync for IterSome<'_, T> {}
+
+impl<T> Clone for IterSome<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data,
+            ones: self.ones,
+            lt: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> Iterator for IterSome<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.ones.next()?;
+
+        // the flag is set, so the element is initialized
+        unsafe { Some((index, &*self.data.as_ptr().add(index))) }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ones.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IterSome<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.ones.next_back()?;
+
+        // the flag is set, so the element is initialized
+        unsafe { Some((index, &*self.data.as_ptr().add(index))) }
+    }
+}
+
+impl<T> ExactSizeIterator for IterSome<'_, T> {}
+impl<T> std::iter::FusedIterator for IterSome<'_, T> {}
+
+/// This struct is created by the `iter_some_mut` method on `VecOption` and `SliceMut`
+pub struct IterSomeMut<'a, T> {
+    data: NonNull<T>,
+    ones: Ones<'a>,
+    lt: PhantomData<&'a mut [T]>,
+}
+
+unsafe impl<T: Send> Send for IterSomeMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterSomeMut<'_, T> {}
+
+impl<'a, T> Iterator for IterSomeMut<'a, T> {
+    type Item = (usize, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.ones.next()?;
+
+        // the flag is set, so the element is initialized, and each index
+        // is only yielded once
+        unsafe { Some((index, &mut *self.data.as_ptr().add(index))) }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ones.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IterSomeMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.ones.next_back()?;
+
+        // the flag is set, so the element is initialized, and each index
+        // is only yielded once
+        unsafe { Some((index, &mut *self.data.as_ptr().add(index))) }
+    }
+}
+
+impl<T> ExactSizeIterator for IterSomeMut<'_, T> {}
+impl<T> std::iter::FusedIterator for IterSomeMut<'_, T> {}
+
+/// This struct is created by the `take_if` method on `VecOption` and `SliceMut`
+pub struct TakeIf<'a, T, F> {
+    slice: SliceMut<'a, T>,
+    index: usize,
+    pred: F,
+}
+
+impl<'a, T, F> TakeIf<'a, T, F> {
+    pub(crate) fn new(slice: SliceMut<'a, T>, pred: F) -> Self {
+        Self {
+            slice,
+            index: 0,
+            pred,
+        }
+    }
+}
+
+impl<T, F: FnMut(&T) -> bool> Iterator for TakeIf<'_, T, F> {
+    type Item = (usize, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(index) = self.slice.flag.find(self.index, true) {
+            self.index = index + 1;
+
+            unsafe {
+                // index was checked by find, and the flag is set so data is initialized
+                let data = self.slice.data.as_ptr().add(index);
+
+                if (self.pred)(&*data) {
+                    self.slice.flag.set(index, false);
+
+                    return Some((index, data.read()));
+                }
+            }
+        }
+
+        self.index = self.slice.len();
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.slice.len() - self.index))
+    }
+}
+
+impl<T, F: FnMut(&T) -> bool> std::iter::FusedIterator for TakeIf<'_, T, F> {}
+
 impl<'a, T> IntoIterator for Slice<'a, T> {
     type Item = Option<&'a T>;
     type IntoIter = Iter<'a, T>;
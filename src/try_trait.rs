@@ -0,0 +1,74 @@
+//! A stable stand-in for `std::ops::Try`
+//!
+//! With the `nightly` feature this is replaced by `std::ops::Try`, so that `try_fold`
+//! and `try_for_each` work with all `Try` types
+
+use std::ops::ControlFlow;
+
+mod private {
+    /// Keeps `Try` from being implemented outside of this crate, so that the
+    /// `nightly` feature can replace it without breaking anyone's impls
+    pub trait Sealed {}
+
+    impl<T, E> Sealed for Result<T, E> {}
+    impl<B, C> Sealed for std::ops::ControlFlow<B, C> {}
+}
+
+/// The types that can be returned from the closures passed to
+/// `try_fold` and `try_for_each`
+///
+/// This is implemented for `Result<T, E>` and `ControlFlow<B, C>`, and is sealed,
+/// so it can't be implemented for other types
+pub trait Try: private::Sealed + Sized {
+    /// The value produced when the operation continues
+    type Output;
+
+    /// The value produced when the operation short-circuits
+    type Residual;
+
+    /// Constructs `Self` from the value produced when the operation continues
+    fn from_output(output: Self::Output) -> Self;
+
+    /// Constructs `Self` from the value produced when the operation short-circuits
+    fn from_residual(residual: Self::Residual) -> Self;
+
+    /// Decides whether the operation should continue or short-circuit
+    fn branch(self) -> ControlFlow<Self::Residual, Self::Output>;
+}
+
+impl<T, E> Try for Result<T, E> {
+    type Output = T;
+    type Residual = E;
+
+    fn from_output(output: Self::Output) -> Self {
+        Ok(output)
+    }
+
+    fn from_residual(residual: Self::Residual) -> Self {
+        Err(residual)
+    }
+
+    fn branch(self) -> ControlFlow<Self::Residual, Self::Output> {
+        match self {
+            Ok(output) => ControlFlow::Continue(output),
+            Err(residual) => ControlFlow::Break(residual),
+        }
+    }
+}
+
+impl<B, C> Try for ControlFlow<B, C> {
+    type Output = C;
+    type Residual = B;
+
+    fn from_output(output: Self::Output) -> Self {
+        ControlFlow::Continue(output)
+    }
+
+    fn from_residual(residual: Self::Residual) -> Self {
+        ControlFlow::Break(residual)
+    }
+
+    fn branch(self) -> ControlFlow<Self::Residual, Self::Output> {
+        self
+    }
+}